@@ -9,24 +9,32 @@ use bitboard::*;
 use typenum::*;
 
 type RealLife = BitBoard<U8, u64>;
+type Large = BitBoard<U100, u64>;
 
-fn real_life() {
+fn real_life() -> RealLife {
     use Move::*;
     use Rotation::*;
 
-    let mut bb = RealLife::new(vec![(4, 4)]);
-    let queen = bb
-        .moves()
+    let bb = RealLife::new(vec![(4, 4)]);
+    bb.moves()
         .translate(Up(1))
         .translate(UpLeft(1, 1))
         .rotate(Clockwise)
         .mirror()
         .repeat(8)
-        .collect();
+        .collect()
+}
+
+fn large_ops() -> usize {
+    let lhs = Large::new((0..100).map(|i| (i, i)).collect());
+    let rhs = Large::new((0..100).map(|i| (99 - i, i)).collect());
+    let shifted = (&lhs | &rhs) << Move::UpRight(3, 7);
+    (&shifted ^ &lhs).count_ones()
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("Moves", |b| b.iter(|| real_life()));
+    c.bench_function("Moves", |b| b.iter(real_life));
+    c.bench_function("Ops 100x100", |b| b.iter(large_ops));
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,40 @@
+use super::*;
+
+// The cells a pattern reaches from each cell of the board, worked out once up front. Meant for
+// leapers, whose moves don't depend on what else is on the board.
+pub struct AttackTable<N: Unsigned, R: PrimUInt = u64> {
+    table: Vec<BitBoard<N, R>>,
+}
+
+impl<N: Unsigned, R: PrimUInt> AttackTable<N, R> {
+    pub fn new(pattern: &MovePattern) -> Self {
+        let s = N::USIZE;
+        let table = (0..s * s)
+            .map(|i| {
+                let from = BitBoard::new(vec![(i % s, i / s)]);
+                from.moves().pattern(pattern).collect()
+            })
+            .collect();
+
+        AttackTable { table }
+    }
+
+    // Cells reached from (x, y). Panics when (x, y) is off the board.
+    pub fn attacks(&self, x: usize, y: usize) -> &BitBoard<N, R> {
+        assert!(
+            x < N::USIZE && y < N::USIZE,
+            "({}, {}) is off the board",
+            x,
+            y
+        );
+        &self.table[x + y * N::USIZE]
+    }
+
+    // Cells reached from any set cell of `from`
+    pub fn attacks_from(&self, from: &BitBoard<N, R>) -> BitBoard<N, R> {
+        let mut result = BitBoard::default();
+        from.ones()
+            .for_each(|(x, y)| result |= &self.table[x + y * N::USIZE]);
+        result
+    }
+}
@@ -0,0 +1,227 @@
+use super::*;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// A step and how many times it can be repeated in a line. Leapers have a range of 1, and
+// riders with no range go until they leave the board.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ray {
+    pub step: Move,
+    pub range: Option<usize>,
+}
+
+// Piece movement in Betza's funny notation, e.g
+//
+//     N      knight
+//     WfF    wazir that can also step diagonally forward
+//     R4     rook limited to 4 squares
+//     NN     nightrider
+//     fsN    knight limited to its two wide forward moves
+//
+// Atoms are W, F, D, N, A, H, C, Z and G, with K, R, B and Q as shorthands. Doubling an
+// atom makes it a rider and a number after it limits the range. The lowercase f, b, l, r,
+// v and s before an atom pick out directions, where forward is up the board. A vertical
+// modifier followed by a sideways one ( fl, bs ) means both at once, and doubling one
+// ( ff, ll ) means the narrow or wide moves in that direction.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Betza {
+    rays: Vec<Ray>,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct BetzaError {
+    // Character position in the notation
+    pub position: usize,
+    // None when the notation ends early
+    pub found: Option<char>,
+}
+
+impl Display for BetzaError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self.found {
+            Some(c) => write!(f, "unexpected {:?} at position {}", c, self.position),
+            None => write!(f, "expected an atom at position {}", self.position),
+        }
+    }
+}
+
+impl std::error::Error for BetzaError {}
+
+impl Betza {
+    pub fn rays(&self) -> &[Ray] {
+        &self.rays
+    }
+
+    // Every cell reachable from a set cell of `from`. Riders stop at the first cell in
+    // `blockers`, which is still included so captures show up. Leapers jump over them.
+    pub fn apply<N: Unsigned, R: PrimUInt>(
+        &self,
+        from: &BitBoard<N, R>,
+        blockers: Option<&BitBoard<N, R>>,
+    ) -> BitBoard<N, R> {
+        let mut result = BitBoard::default();
+        for ray in &self.rays {
+            let range = ray.range.unwrap_or(N::USIZE);
+            match blockers {
+                None => result |= &from.moves().translate(ray.step).repeat(range).collect(),
+                Some(blockers) => {
+                    let open = !blockers;
+                    let mut frontier = from.clone();
+                    for _ in 0..range {
+                        frontier <<= ray.step;
+                        if frontier.count_ones() == 0 {
+                            break;
+                        }
+                        result |= &frontier;
+                        frontier &= &open;
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn push(&mut self, ray: Ray) {
+        if !self.rays.contains(&ray) {
+            self.rays.push(ray);
+        }
+    }
+}
+
+// (x, y) of the first step of each atom, before any symmetry is applied
+fn atom(c: char) -> Option<(usize, usize)> {
+    match c {
+        'W' => Some((0, 1)),
+        'F' => Some((1, 1)),
+        'D' => Some((0, 2)),
+        'N' => Some((1, 2)),
+        'A' => Some((2, 2)),
+        'H' => Some((0, 3)),
+        'C' => Some((1, 3)),
+        'Z' => Some((2, 3)),
+        'G' => Some((3, 3)),
+        _ => None,
+    }
+}
+
+// Shorthands and whether they ride
+fn compound(c: char) -> Option<(&'static [char], bool)> {
+    match c {
+        'K' => Some((&['W', 'F'], false)),
+        'R' => Some((&['W'], true)),
+        'B' => Some((&['F'], true)),
+        'Q' => Some((&['W', 'F'], true)),
+        _ => None,
+    }
+}
+
+// The moves of an atom in every direction, built up from one of them with the rotate and
+// mirror transformations the Moves builder uses
+fn atom_moves((x, y): (usize, usize)) -> Vec<Move> {
+    let mut moves = vec![Move::from_offset(x as isize, y as isize)];
+    if x != y {
+        moves.push(Move::from_offset(y as isize, x as isize));
+    }
+    rotate_moves(&mut moves, Rotation::Clockwise);
+    mirror_moves(&mut moves);
+
+    let mut unique: Vec<Move> = Vec::with_capacity(moves.len());
+    moves.into_iter().for_each(|m| {
+        if !unique.iter().any(|u| u.offset() == m.offset()) {
+            unique.push(m);
+        }
+    });
+    unique
+}
+
+// Whether a move matches all of a group of direction modifiers
+fn matches(m: Move, group: &[char]) -> bool {
+    let (x, y) = m.offset();
+    group.iter().all(|d| match d {
+        'f' => y > 0,
+        'b' => y < 0,
+        'l' => x < 0,
+        'r' => x > 0,
+        'v' => y.abs() > x.abs(),
+        's' => x.abs() > y.abs(),
+        _ => false,
+    })
+}
+
+// Splits modifiers into groups that are each a single direction
+fn direction_groups(modifiers: &[char]) -> Vec<Vec<char>> {
+    let mut groups: Vec<Vec<char>> = Vec::new();
+    let mut i = 0;
+    while i < modifiers.len() {
+        let (c, next) = (modifiers[i], modifiers.get(i + 1).copied());
+        let group = match (c, next) {
+            ('f', Some('f')) | ('b', Some('b')) => vec![c, 'v'],
+            ('l', Some('l')) | ('r', Some('r')) => vec![c, 's'],
+            ('f', Some(n)) | ('b', Some(n)) if "lrs".contains(n) => vec![c, n],
+            _ => vec![c],
+        };
+        i += if group.len() == 2 { 2 } else { 1 };
+        groups.push(group);
+    }
+    groups
+}
+
+impl FromStr for Betza {
+    type Err = BetzaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut result = Betza::default();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let start = i;
+            while i < chars.len() && "fblrsv".contains(chars[i]) {
+                i += 1;
+            }
+            let groups = direction_groups(&chars[start..i]);
+
+            let error = BetzaError {
+                position: i,
+                found: chars.get(i).copied(),
+            };
+            let c = *chars.get(i).ok_or(error)?;
+            let (atoms, mut rider): (Vec<char>, bool) = match (atom(c), compound(c)) {
+                (Some(_), _) => (vec![c], false),
+                (_, Some((atoms, rider))) => (atoms.to_vec(), rider),
+                _ => return Err(error),
+            };
+            i += 1;
+
+            if !rider && atoms.len() == 1 && chars.get(i) == Some(&c) {
+                rider = true;
+                i += 1;
+            }
+            let digits = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let range = match (digits < i, rider) {
+                (true, _) => {
+                    let number: String = chars[digits..i].iter().collect();
+                    Some(number.parse().map_err(|_| BetzaError {
+                        position: digits,
+                        found: Some(chars[digits]),
+                    })?)
+                }
+                (false, true) => None,
+                (false, false) => Some(1),
+            };
+
+            for step in atoms.iter().flat_map(|&a| atom_moves(atom(a).unwrap())) {
+                if groups.is_empty() || groups.iter().any(|g| matches(step, g)) {
+                    result.push(Ray { step, range });
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
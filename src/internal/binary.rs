@@ -0,0 +1,130 @@
+use super::convert::block_bytes;
+use super::*;
+use num::NumCast;
+use std::fmt::{Display, Formatter};
+use std::io::{self, Read, Write};
+use std::mem;
+
+// The binary format is an 8 byte header followed by the blocks, least significant byte first
+//
+//     magic      2 bytes   "BB"
+//     version    1 byte    FORMAT_VERSION
+//     block size 1 byte    size of R in bytes
+//     dimension  4 bytes   N, little endian
+//     blocks               REQUIRED_BLOCKS * block size bytes
+//
+// The layout is pinned by the golden files in src/golden, so any change to it needs a new
+// version number.
+pub const FORMAT_VERSION: u8 = 1;
+
+const MAGIC: &[u8; 2] = b"BB";
+
+#[derive(Debug)]
+pub enum BinaryError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u8),
+    SizeMismatch { expected: usize, found: usize },
+    BlockMismatch { expected: usize, found: usize },
+    // Bits past the last cell of the board are set
+    OutOfBounds,
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            BinaryError::Io(e) => write!(f, "io error: {}", e),
+            BinaryError::BadMagic => write!(f, "not a serialized board"),
+            BinaryError::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+            BinaryError::SizeMismatch { expected, found } => write!(
+                f,
+                "expected a {e}x{e} board, found {f}x{f}",
+                e = expected,
+                f = found
+            ),
+            BinaryError::BlockMismatch { expected, found } => write!(
+                f,
+                "expected {} byte blocks, found {} byte blocks",
+                expected, found
+            ),
+            BinaryError::OutOfBounds => write!(f, "bits are set outside of the board"),
+        }
+    }
+}
+
+impl std::error::Error for BinaryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BinaryError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BinaryError {
+    fn from(e: io::Error) -> Self {
+        BinaryError::Io(e)
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
+    pub fn write_to<W: Write>(&self, mut w: W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&[FORMAT_VERSION, Self::BLOCK_SIZE as u8])?;
+        w.write_all(&(N::U32).to_le_bytes())?;
+
+        let bytes: Vec<u8> = self.blocks().iter().flat_map(|&b| block_bytes(b)).collect();
+        w.write_all(&bytes)
+    }
+
+    // Reads a board written by write_to, checking it was written for the same N and R
+    pub fn read_from<Rd: Read>(mut r: Rd) -> Result<Self, BinaryError> {
+        let mut header = [0u8; 8];
+        r.read_exact(&mut header)?;
+
+        if &header[..2] != MAGIC {
+            return Err(BinaryError::BadMagic);
+        }
+        if header[2] != FORMAT_VERSION {
+            return Err(BinaryError::UnsupportedVersion(header[2]));
+        }
+        if header[3] as usize != Self::BLOCK_SIZE {
+            return Err(BinaryError::BlockMismatch {
+                expected: Self::BLOCK_SIZE,
+                found: header[3] as usize,
+            });
+        }
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if size != N::USIZE {
+            return Err(BinaryError::SizeMismatch {
+                expected: N::USIZE,
+                found: size,
+            });
+        }
+
+        let mut bytes = vec![0u8; Self::REQUIRED_BYTES];
+        r.read_exact(&mut bytes)?;
+
+        let mut result = Self::default();
+        for (block, chunk) in result
+            .blocks_mut()
+            .iter_mut()
+            .zip(bytes.chunks(mem::size_of::<R>()))
+        {
+            *block = chunk.iter().enumerate().fold(R::zero(), |acc, (i, &b)| {
+                let b: R = NumCast::from(b).unwrap();
+                acc | b << (8 * i)
+            });
+        }
+
+        let mask: R = last_block_mask(Self::BOARD_SIZE);
+        if result
+            .blocks()
+            .last()
+            .is_some_and(|&b| b & !mask != R::zero())
+        {
+            return Err(BinaryError::OutOfBounds);
+        }
+        Ok(result)
+    }
+}
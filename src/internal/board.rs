@@ -1,7 +1,8 @@
-use super::PrimUInt;
+use super::{parallel, PrimUInt};
 use std::alloc;
 use std::fmt::{Debug, Display, Formatter};
 use std::marker::PhantomData;
+use std::slice;
 use typenum::Unsigned;
 
 pub struct BitBoard<N: Unsigned, R: PrimUInt = u64> {
@@ -43,7 +44,11 @@ impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
     }
 
     pub fn count_ones(&self) -> usize {
-        unsafe { self.block_iter().map(|b| b.count_ones() as usize).sum() }
+        if Self::PARALLEL {
+            parallel::map_reduce(self.blocks(), Self::BLOCK_SIZE, count_kernel, |a, b| a + b)
+        } else {
+            count_kernel(self.blocks())
+        }
     }
 
     fn in_bounds(x: usize, y: usize) -> bool {
@@ -52,8 +57,8 @@ impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
 
     fn map_coords(x: usize, y: usize) -> (isize, R) {
         let pos = x + y * N::USIZE;
-        let byte_offset = pos / Self::ALIGNMENT_BITS;
-        let bit_pos: R = R::one() << (pos % Self::ALIGNMENT_BITS);
+        let byte_offset = pos / Self::BLOCK_SIZE_BITS;
+        let bit_pos: R = R::one() << (pos % Self::BLOCK_SIZE_BITS);
 
         (byte_offset as isize, bit_pos)
     }
@@ -67,6 +72,26 @@ impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
     pub(super) unsafe fn block_at_mut(&mut self, i: isize) -> *mut R {
         self.ptr.offset(i)
     }
+
+    #[inline(always)]
+    pub(super) fn blocks(&self) -> &[R] {
+        unsafe { slice::from_raw_parts(self.ptr, Self::REQUIRED_BLOCKS) }
+    }
+
+    #[inline(always)]
+    pub(super) fn blocks_mut(&mut self) -> &mut [R] {
+        unsafe { slice::from_raw_parts_mut(self.ptr, Self::REQUIRED_BLOCKS) }
+    }
+}
+
+#[cfg(not(feature = "simd"))]
+pub(super) fn count_kernel<R: PrimUInt>(blocks: &[R]) -> usize {
+    blocks.iter().map(|b| b.count_ones() as usize).sum()
+}
+
+#[cfg(feature = "simd")]
+pub(super) fn count_kernel<R: PrimUInt>(blocks: &[R]) -> usize {
+    super::simd::count_ones(super::simd::bytes(blocks))
 }
 
 impl<N: Unsigned, R: PrimUInt> Default for BitBoard<N, R> {
@@ -130,23 +155,19 @@ impl<N: Unsigned, R: PrimUInt> Debug for BitBoard<N, R> {
         writeln!(f, "Last Block Mask : {:b}", Self::last_block_mask())?;
         writeln!(f, "Data            : {:?}", self.ptr)?;
         unsafe {
-            self.block_iter().rev().for_each(|block| {
+            for block in self.block_iter().rev() {
                 for i in 0..Self::BLOCK_SIZE_BITS {
                     let shift: R = R::one() << (Self::BLOCK_SIZE_BITS - i - 1);
 
                     if block & shift != R::zero() {
-                        if write!(f, "1").is_err() {
-                            return;
-                        }
-                    } else if write!(f, "0").is_err() {
-                        return;
+                        write!(f, "1")?;
+                    } else {
+                        write!(f, "0")?;
                     }
                 }
 
-                if write!(f, " ").is_err() {
-                    return;
-                }
-            });
+                write!(f, " ")?;
+            }
             writeln!(f)?;
         }
 
@@ -156,18 +177,25 @@ impl<N: Unsigned, R: PrimUInt> Debug for BitBoard<N, R> {
 
 impl<N: Unsigned, R: PrimUInt> Display for BitBoard<N, R> {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        let s = N::USIZE;
-
-        for c in 0..s {
-            for r in 0..s {
-                if self.is_set(r, s - c - 1) {
-                    write!(f, "1 ")?;
-                } else {
-                    write!(f, "0 ")?;
-                }
+        write_grid(f, N::USIZE, |x, y| self.is_set(x, y))
+    }
+}
+
+// The 1 and 0 grid boards display as, top rank first. Shared with DynBitBoard
+pub(super) fn write_grid(
+    f: &mut Formatter,
+    s: usize,
+    is_set: impl Fn(usize, usize) -> bool,
+) -> std::fmt::Result {
+    for c in 0..s {
+        for r in 0..s {
+            if is_set(r, s - c - 1) {
+                write!(f, "1 ")?;
+            } else {
+                write!(f, "0 ")?;
             }
-            writeln!(f)?;
         }
-        write!(f, "")
+        writeln!(f)?;
     }
+    write!(f, "")
 }
@@ -0,0 +1,252 @@
+use super::ops::{and_kernel, or_kernel, xor_kernel};
+use super::*;
+use std::fmt::{Display, Formatter};
+use std::mem;
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, ShlAssign,
+};
+
+// A WxH board stored inline as an array of blocks, sized with plain integers.
+// Stable Rust can't size the array from an expression over W and H yet, so the block count B
+// is a parameter of its own. It must equal `required_blocks_for::<R>(W, H)`, which is
+// checked at compile time, e.g
+//
+//     type Go = ConstBitBoard<19, 19, { required_blocks_for::<u64>(19, 19) }>;
+//
+// Cells are laid out the same way as in BitBoard, so square boards convert to and from
+// BitBoard<N, R> with a straight copy of the blocks.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ConstBitBoard<const W: usize, const H: usize, const B: usize, R: PrimUInt = u64> {
+    blocks: [R; B],
+}
+
+pub type ConstBitBoard3x3 = ConstBitBoard<3, 3, 1, u16>;
+pub type ConstBitBoard4x4 = ConstBitBoard<4, 4, 1, u16>;
+pub type ConstBitBoard5x5 = ConstBitBoard<5, 5, 1, u32>;
+pub type ConstBitBoard6x6 = ConstBitBoard<6, 6, 1, u64>;
+pub type ConstBitBoard7x7 = ConstBitBoard<7, 7, 1, u64>;
+pub type ConstBitBoard8x8 = ConstBitBoard<8, 8, 1, u64>;
+pub type ConstBitBoard11x11 = ConstBitBoard<11, 11, 1, u128>;
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> ConstBitBoard<W, H, B, R> {
+    pub const WIDTH: usize = W;
+    pub const HEIGHT: usize = H;
+    pub const BOARD_SIZE: usize = W * H;
+    pub const BLOCK_SIZE_BITS: usize = mem::size_of::<R>() * 8;
+
+    const SHAPE: Shape = Shape {
+        width: W,
+        height: H,
+        parallel: false,
+    };
+
+    pub fn new(initial: Vec<(usize, usize)>) -> Self {
+        let mut result = Self::default();
+        initial.iter().for_each(|(x, y)| {
+            result.set(*x, *y);
+        });
+
+        result
+    }
+
+    pub fn set(&mut self, x: usize, y: usize) {
+        if Self::in_bounds(x, y) {
+            let (offset, bit_pos) = Self::map_coords(x, y);
+            self.blocks[offset] |= bit_pos;
+        }
+    }
+
+    pub fn unset(&mut self, x: usize, y: usize) {
+        if Self::in_bounds(x, y) {
+            let (offset, bit_pos) = Self::map_coords(x, y);
+            self.blocks[offset] &= !bit_pos;
+        }
+    }
+
+    pub fn is_set(&self, x: usize, y: usize) -> bool {
+        if Self::in_bounds(x, y) {
+            let (offset, bit_pos) = Self::map_coords(x, y);
+            self.blocks[offset] & bit_pos != R::zero()
+        } else {
+            false
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.blocks.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    fn in_bounds(x: usize, y: usize) -> bool {
+        x < W && y < H
+    }
+
+    fn map_coords(x: usize, y: usize) -> (usize, R) {
+        let pos = x + y * W;
+        (
+            pos / Self::BLOCK_SIZE_BITS,
+            R::one() << (pos % Self::BLOCK_SIZE_BITS),
+        )
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> Default
+    for ConstBitBoard<W, H, B, R>
+{
+    fn default() -> Self {
+        const {
+            assert!(
+                B == required_blocks_for::<R>(W, H),
+                "B must be required_blocks_for::<R>(W, H)"
+            )
+        };
+        ConstBitBoard {
+            blocks: [R::zero(); B],
+        }
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt, const W: usize, const H: usize, const B: usize> From<&BitBoard<N, R>>
+    for ConstBitBoard<W, H, B, R>
+{
+    fn from(board: &BitBoard<N, R>) -> Self {
+        const { assert!(W == N::USIZE && H == N::USIZE, "board sizes don't match") };
+        let mut result = Self::default();
+        result.blocks.copy_from_slice(board.blocks());
+        result
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt, const W: usize, const H: usize, const B: usize> From<BitBoard<N, R>>
+    for ConstBitBoard<W, H, B, R>
+{
+    fn from(board: BitBoard<N, R>) -> Self {
+        Self::from(&board)
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt, const W: usize, const H: usize, const B: usize>
+    From<&ConstBitBoard<W, H, B, R>> for BitBoard<N, R>
+{
+    fn from(board: &ConstBitBoard<W, H, B, R>) -> Self {
+        const { assert!(W == N::USIZE && H == N::USIZE, "board sizes don't match") };
+        let mut result = Self::default();
+        result.blocks_mut().copy_from_slice(&board.blocks);
+        result
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt, const W: usize, const H: usize, const B: usize>
+    From<ConstBitBoard<W, H, B, R>> for BitBoard<N, R>
+{
+    fn from(board: ConstBitBoard<W, H, B, R>) -> Self {
+        Self::from(&board)
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> Shl<Move>
+    for ConstBitBoard<W, H, B, R>
+{
+    type Output = Self;
+
+    fn shl(mut self, rhs: Move) -> Self::Output {
+        self <<= rhs;
+        self
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> ShlAssign<Move>
+    for ConstBitBoard<W, H, B, R>
+{
+    fn shl_assign(&mut self, rhs: Move) {
+        shift_move(&mut self.blocks, Self::SHAPE, rhs);
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> BitAnd
+    for ConstBitBoard<W, H, B, R>
+{
+    type Output = Self;
+
+    fn bitand(mut self, rhs: Self) -> Self::Output {
+        self &= rhs;
+        self
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> BitAndAssign
+    for ConstBitBoard<W, H, B, R>
+{
+    fn bitand_assign(&mut self, rhs: Self) {
+        and_kernel(&mut self.blocks, &rhs.blocks);
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> BitOr
+    for ConstBitBoard<W, H, B, R>
+{
+    type Output = Self;
+
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self |= rhs;
+        self
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> BitOrAssign
+    for ConstBitBoard<W, H, B, R>
+{
+    fn bitor_assign(&mut self, rhs: Self) {
+        or_kernel(&mut self.blocks, &rhs.blocks);
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> BitXor
+    for ConstBitBoard<W, H, B, R>
+{
+    type Output = Self;
+
+    fn bitxor(mut self, rhs: Self) -> Self::Output {
+        self ^= rhs;
+        self
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> BitXorAssign
+    for ConstBitBoard<W, H, B, R>
+{
+    fn bitxor_assign(&mut self, rhs: Self) {
+        xor_kernel(&mut self.blocks, &rhs.blocks);
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> Not
+    for ConstBitBoard<W, H, B, R>
+{
+    type Output = Self;
+
+    fn not(mut self) -> Self::Output {
+        self.blocks.iter_mut().for_each(|block| *block = !*block);
+        if let Some(block) = self.blocks.last_mut() {
+            *block &= last_block_mask(W * H);
+        }
+        self
+    }
+}
+
+impl<const W: usize, const H: usize, const B: usize, R: PrimUInt> Display
+    for ConstBitBoard<W, H, B, R>
+{
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for c in 0..H {
+            for r in 0..W {
+                if self.is_set(r, H - c - 1) {
+                    write!(f, "1 ")?;
+                } else {
+                    write!(f, "0 ")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        write!(f, "")
+    }
+}
@@ -0,0 +1,155 @@
+use super::*;
+use num::NumCast;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::mem;
+
+// Returned by from_blocks when the blocks can't be a board of that size
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlocksError {
+    WrongLength { expected: usize, found: usize },
+    // Bits past the last cell of the board are set
+    OutOfBounds,
+}
+
+impl Display for BlocksError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            BlocksError::WrongLength { expected, found } => {
+                write!(f, "expected {} blocks, found {}", expected, found)
+            }
+            BlocksError::OutOfBounds => write!(f, "bits are set outside of the board"),
+        }
+    }
+}
+
+impl std::error::Error for BlocksError {}
+
+impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
+    // The same board packed into R2 blocks. Cells keep their coordinates because both
+    // layouts store cell x + y * N at that bit of the little endian stream of blocks.
+    pub fn convert<R2: PrimUInt>(&self) -> BitBoard<N, R2> {
+        let mut result = BitBoard::<N, R2>::default();
+        let size = mem::size_of::<R2>();
+        let blocks = result.blocks_mut();
+
+        self.blocks()
+            .iter()
+            .flat_map(|&block| block_bytes(block))
+            .take(BitBoard::<N, R2>::REQUIRED_BYTES)
+            .enumerate()
+            .for_each(|(i, byte)| {
+                let byte: R2 = NumCast::from(byte).unwrap();
+                blocks[i / size] |= byte << (8 * (i % size));
+            });
+
+        result
+    }
+
+    // A copy of the packed blocks, lowest cells first
+    pub fn to_blocks(&self) -> Vec<R> {
+        self.blocks().to_vec()
+    }
+
+    pub fn from_blocks(blocks: &[R]) -> Result<Self, BlocksError> {
+        if blocks.len() != Self::REQUIRED_BLOCKS {
+            return Err(BlocksError::WrongLength {
+                expected: Self::REQUIRED_BLOCKS,
+                found: blocks.len(),
+            });
+        }
+
+        let mask: R = last_block_mask(Self::BOARD_SIZE);
+        if blocks
+            .last()
+            .is_some_and(|&block| block & !mask != R::zero())
+        {
+            return Err(BlocksError::OutOfBounds);
+        }
+
+        let mut result = Self::default();
+        result.blocks_mut().copy_from_slice(blocks);
+        Ok(result)
+    }
+}
+
+// The bytes of a block, least significant first
+pub(super) fn block_bytes<R: PrimUInt>(block: R) -> impl Iterator<Item = u8> {
+    let low: R = NumCast::from(0xff).unwrap();
+    (0..mem::size_of::<R>()).map(move |i| ((block >> (8 * i)) & low).to_u8().unwrap())
+}
+
+// From can't be implemented generically over both block types without overlapping the
+// blanket From<T> for T, so each pair is spelled out
+macro_rules! convert_from {
+    ($from:ty => $($to:ty),+) => {
+        $(
+            impl<N: Unsigned> From<&BitBoard<N, $from>> for BitBoard<N, $to> {
+                fn from(board: &BitBoard<N, $from>) -> Self {
+                    board.convert()
+                }
+            }
+
+            impl<N: Unsigned> From<BitBoard<N, $from>> for BitBoard<N, $to> {
+                fn from(board: BitBoard<N, $from>) -> Self {
+                    board.convert()
+                }
+            }
+        )+
+    };
+}
+
+convert_from!(u8 => u16, u32, u64, u128);
+convert_from!(u16 => u8, u32, u64, u128);
+convert_from!(u32 => u8, u16, u64, u128);
+convert_from!(u64 => u8, u16, u32, u128);
+convert_from!(u128 => u8, u16, u32, u64);
+
+// Boards that fit in a single block can be used as the raw integer, e.g. as a hash key.
+// Using these on a board with more than one block fails to compile.
+impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
+    pub fn as_raw(&self) -> R {
+        const { assert!(Self::REQUIRED_BLOCKS == 1, "board doesn't fit in one block") };
+        self.blocks()[0]
+    }
+
+    pub fn from_raw(raw: R) -> Result<Self, BlocksError> {
+        const { assert!(Self::REQUIRED_BLOCKS == 1, "board doesn't fit in one block") };
+        if raw & !last_block_mask::<R>(Self::BOARD_SIZE) != R::zero() {
+            return Err(BlocksError::OutOfBounds);
+        }
+
+        let mut result = Self::default();
+        result.blocks_mut()[0] = raw;
+        Ok(result)
+    }
+}
+
+// As with From, the orphan rules need the block type spelled out
+macro_rules! raw_from {
+    ($($raw:ty),+) => {
+        $(
+            impl<N: Unsigned> From<&BitBoard<N, $raw>> for $raw {
+                fn from(board: &BitBoard<N, $raw>) -> Self {
+                    board.as_raw()
+                }
+            }
+
+            impl<N: Unsigned> From<BitBoard<N, $raw>> for $raw {
+                fn from(board: BitBoard<N, $raw>) -> Self {
+                    board.as_raw()
+                }
+            }
+
+            impl<N: Unsigned> TryFrom<$raw> for BitBoard<N, $raw> {
+                type Error = BlocksError;
+
+                fn try_from(raw: $raw) -> Result<Self, Self::Error> {
+                    BitBoard::from_raw(raw)
+                }
+            }
+        )+
+    };
+}
+
+raw_from!(u8, u16, u32, u64, u128);
@@ -0,0 +1,259 @@
+use super::*;
+use std::fmt::{Display, Formatter};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Shl, ShlAssign};
+
+// Movement along the Z axis. Forward moves towards higher planes
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Depth {
+    Level,
+    Forward(usize),
+    Back(usize),
+}
+
+// A move in three dimensions, made up of a move within the plane and a move between planes
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Move3D {
+    pub planar: Move,
+    pub depth: Depth,
+}
+
+impl Move3D {
+    pub fn new(planar: Move, depth: Depth) -> Self {
+        Move3D { planar, depth }
+    }
+
+    // The 26 single step moves to every neighbouring cell of a cube
+    pub fn directions() -> Vec<Move3D> {
+        use Move::*;
+        let planar = [
+            NullMove,
+            Up(1),
+            Down(1),
+            Left(1),
+            Right(1),
+            UpLeft(1, 1),
+            UpRight(1, 1),
+            DownLeft(1, 1),
+            DownRight(1, 1),
+        ];
+        let depths = [Depth::Level, Depth::Forward(1), Depth::Back(1)];
+
+        depths
+            .iter()
+            .flat_map(|&d| planar.iter().map(move |&m| Move3D::new(m, d)))
+            .filter(|m| *m != Move3D::new(NullMove, Depth::Level))
+            .collect()
+    }
+
+    pub fn mirror(self) -> Self {
+        let depth = match self.depth {
+            Depth::Forward(i) => Depth::Back(i),
+            Depth::Back(i) => Depth::Forward(i),
+            Depth::Level => Depth::Level,
+        };
+        Move3D::new(self.planar.mirror(), depth)
+    }
+}
+
+impl From<Move> for Move3D {
+    fn from(planar: Move) -> Self {
+        Move3D::new(planar, Depth::Level)
+    }
+}
+
+// An NxNxN board made up of N planes, with plane z holding the cells at depth z
+#[derive(Clone, Debug)]
+pub struct BitBoard3D<N: Unsigned, R: PrimUInt = u64> {
+    planes: Vec<BitBoard<N, R>>,
+}
+
+impl<N: Unsigned, R: PrimUInt> BitBoard3D<N, R> {
+    pub fn new(initial: Vec<(usize, usize, usize)>) -> Self {
+        let mut result = Self::default();
+        initial.iter().for_each(|(x, y, z)| {
+            result.set(*x, *y, *z);
+        });
+
+        result
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize) {
+        if let Some(plane) = self.planes.get_mut(z) {
+            plane.set(x, y);
+        }
+    }
+
+    pub fn unset(&mut self, x: usize, y: usize, z: usize) {
+        if let Some(plane) = self.planes.get_mut(z) {
+            plane.unset(x, y);
+        }
+    }
+
+    pub fn is_set(&self, x: usize, y: usize, z: usize) -> bool {
+        self.planes.get(z).is_some_and(|plane| plane.is_set(x, y))
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.planes.iter().map(|plane| plane.count_ones()).sum()
+    }
+
+    pub fn plane(&self, z: usize) -> &BitBoard<N, R> {
+        &self.planes[z]
+    }
+
+    pub fn set_plane(&mut self, z: usize, plane: BitBoard<N, R>) {
+        self.planes[z] = plane;
+    }
+
+    // Every straight line of `length` cells through the cube, along the 13 axes, face
+    // diagonals and space diagonals. With `length == N` these are the winning lines for
+    // games like Qubic.
+    pub fn lines(length: usize) -> Vec<BitBoard3D<N, R>> {
+        let n = N::USIZE as isize;
+        let length = length as isize;
+        if length == 0 || length > n {
+            return Vec::new();
+        }
+
+        // Only one of each pair of opposite directions, so each line is found once
+        let directions = (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| (x, y, z))))
+            .filter(|&d| d > (0, 0, 0));
+        let in_bounds = |i: isize| i >= 0 && i < n;
+
+        let mut result = Vec::new();
+        for (dx, dy, dz) in directions {
+            for (x, y, z) in
+                (0..n).flat_map(|x| (0..n).flat_map(move |y| (0..n).map(move |z| (x, y, z))))
+            {
+                let end = (
+                    x + dx * (length - 1),
+                    y + dy * (length - 1),
+                    z + dz * (length - 1),
+                );
+                if in_bounds(end.0) && in_bounds(end.1) && in_bounds(end.2) {
+                    let cells = (0..length)
+                        .map(|i| {
+                            (
+                                (x + dx * i) as usize,
+                                (y + dy * i) as usize,
+                                (z + dz * i) as usize,
+                            )
+                        })
+                        .collect();
+                    result.push(BitBoard3D::new(cells));
+                }
+            }
+        }
+
+        result
+    }
+
+    fn shift(&mut self, m: Move3D) {
+        self.planes.iter_mut().for_each(|plane| *plane <<= m.planar);
+
+        match m.depth {
+            Depth::Level => (),
+            Depth::Forward(i) if i < N::USIZE => {
+                self.planes.rotate_right(i);
+                self.planes[..i]
+                    .iter_mut()
+                    .for_each(|p| *p = BitBoard::default());
+            }
+            Depth::Back(i) if i < N::USIZE => {
+                self.planes.rotate_left(i);
+                let n = N::USIZE;
+                self.planes[n - i..]
+                    .iter_mut()
+                    .for_each(|p| *p = BitBoard::default());
+            }
+            _ => self
+                .planes
+                .iter_mut()
+                .for_each(|p| *p = BitBoard::default()),
+        }
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Default for BitBoard3D<N, R> {
+    fn default() -> Self {
+        BitBoard3D {
+            planes: (0..N::USIZE).map(|_| BitBoard::default()).collect(),
+        }
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Shl<Move3D> for &BitBoard3D<N, R> {
+    type Output = BitBoard3D<N, R>;
+
+    fn shl(self, rhs: Move3D) -> Self::Output {
+        let mut result = self.clone();
+        result.shift(rhs);
+        result
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Shl<Move3D> for BitBoard3D<N, R> {
+    type Output = Self;
+
+    fn shl(mut self, rhs: Move3D) -> Self::Output {
+        self.shift(rhs);
+        self
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> ShlAssign<Move3D> for BitBoard3D<N, R> {
+    fn shl_assign(&mut self, rhs: Move3D) {
+        self.shift(rhs);
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> BitAnd for &BitBoard3D<N, R> {
+    type Output = BitBoard3D<N, R>;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result &= rhs;
+        result
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> BitAndAssign<&Self> for BitBoard3D<N, R> {
+    fn bitand_assign(&mut self, rhs: &Self) {
+        self.planes
+            .iter_mut()
+            .zip(rhs.planes.iter())
+            .for_each(|(l, r)| *l &= r);
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> BitOr for &BitBoard3D<N, R> {
+    type Output = BitBoard3D<N, R>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result |= rhs;
+        result
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> BitOrAssign<&Self> for BitBoard3D<N, R> {
+    fn bitor_assign(&mut self, rhs: &Self) {
+        self.planes
+            .iter_mut()
+            .zip(rhs.planes.iter())
+            .for_each(|(l, r)| *l |= r);
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Display for BitBoard3D<N, R> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for (z, plane) in self.planes.iter().enumerate() {
+            writeln!(f, "z = {}", z)?;
+            write!(f, "{}", plane)?;
+        }
+        Ok(())
+    }
+}
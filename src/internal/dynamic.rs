@@ -0,0 +1,415 @@
+use super::board::{count_kernel, write_grid};
+use super::ops::{and_kernel, or_kernel, xor_kernel, zip_blocks};
+use super::statics::PARALLEL_THRESHOLD_BYTES;
+use super::*;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::mem;
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, ShlAssign,
+};
+
+// A BitBoard whose dimension is chosen at runtime.
+// Cells are laid out exactly as they are in a BitBoard<N, R> of the same size, so
+// converting between the two is a straight copy of the blocks.
+// Operations between two boards of different sizes panic.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DynBitBoard<R: PrimUInt = u64> {
+    size: usize,
+    blocks: Vec<R>,
+}
+
+// Returned when converting a DynBitBoard to a BitBoard of a different size
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SizeMismatch {
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl Display for SizeMismatch {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "expected a {e}x{e} board, found {f}x{f}",
+            e = self.expected,
+            f = self.found
+        )
+    }
+}
+
+impl std::error::Error for SizeMismatch {}
+
+impl<R: PrimUInt> DynBitBoard<R> {
+    pub fn new(size: usize, initial: Vec<(usize, usize)>) -> Self {
+        let mut result = Self::empty(size);
+        initial.iter().for_each(|(x, y)| {
+            result.set(*x, *y);
+        });
+
+        result
+    }
+
+    pub fn empty(size: usize) -> Self {
+        DynBitBoard {
+            size,
+            blocks: vec![R::zero(); required_blocks_for::<R>(size, size)],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn set(&mut self, x: usize, y: usize) {
+        if self.in_bounds(x, y) {
+            let (offset, bit_pos) = self.map_coords(x, y);
+            self.blocks[offset] |= bit_pos;
+        }
+    }
+
+    pub fn unset(&mut self, x: usize, y: usize) {
+        if self.in_bounds(x, y) {
+            let (offset, bit_pos) = self.map_coords(x, y);
+            self.blocks[offset] &= !bit_pos;
+        }
+    }
+
+    pub fn is_set(&self, x: usize, y: usize) -> bool {
+        if self.in_bounds(x, y) {
+            let (offset, bit_pos) = self.map_coords(x, y);
+            self.blocks[offset] & bit_pos != R::zero()
+        } else {
+            false
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        if self.parallel() {
+            parallel::map_reduce(&self.blocks, mem::size_of::<R>(), count_kernel, |a, b| {
+                a + b
+            })
+        } else {
+            count_kernel(&self.blocks)
+        }
+    }
+
+    pub fn moves(&self) -> DynMoves<'_, R> {
+        DynMoves::new(self)
+    }
+
+    fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.size && y < self.size
+    }
+
+    fn map_coords(&self, x: usize, y: usize) -> (usize, R) {
+        let block_bits = mem::size_of::<R>() * 8;
+        let pos = x + y * self.size;
+        (pos / block_bits, R::one() << (pos % block_bits))
+    }
+
+    fn parallel(&self) -> bool {
+        cfg!(feature = "parallel")
+            && self.blocks.len() * mem::size_of::<R>() >= PARALLEL_THRESHOLD_BYTES
+    }
+
+    fn shape(&self) -> Shape {
+        Shape {
+            width: self.size,
+            height: self.size,
+            parallel: self.parallel(),
+        }
+    }
+
+    fn zip(&mut self, rhs: &Self, kernel: fn(&mut [R], &[R])) {
+        assert_eq!(self.size, rhs.size, "DynBitBoard sizes don't match");
+        let parallel = self.parallel();
+        zip_blocks(&mut self.blocks, &rhs.blocks, parallel, kernel);
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> From<&BitBoard<N, R>> for DynBitBoard<R> {
+    fn from(board: &BitBoard<N, R>) -> Self {
+        DynBitBoard {
+            size: N::USIZE,
+            blocks: board.blocks().to_vec(),
+        }
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> From<BitBoard<N, R>> for DynBitBoard<R> {
+    fn from(board: BitBoard<N, R>) -> Self {
+        DynBitBoard::from(&board)
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> TryFrom<&DynBitBoard<R>> for BitBoard<N, R> {
+    type Error = SizeMismatch;
+
+    fn try_from(board: &DynBitBoard<R>) -> Result<Self, Self::Error> {
+        if board.size != N::USIZE {
+            return Err(SizeMismatch {
+                expected: N::USIZE,
+                found: board.size,
+            });
+        }
+
+        let mut result = BitBoard::default();
+        result.blocks_mut().copy_from_slice(&board.blocks);
+        Ok(result)
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> TryFrom<DynBitBoard<R>> for BitBoard<N, R> {
+    type Error = SizeMismatch;
+
+    fn try_from(board: DynBitBoard<R>) -> Result<Self, Self::Error> {
+        BitBoard::try_from(&board)
+    }
+}
+
+impl<R: PrimUInt> Shl<Move> for &DynBitBoard<R> {
+    type Output = DynBitBoard<R>;
+
+    fn shl(self, rhs: Move) -> Self::Output {
+        let mut result = self.clone();
+        result <<= rhs;
+        result
+    }
+}
+
+impl<R: PrimUInt> Shl<Move> for DynBitBoard<R> {
+    type Output = Self;
+
+    fn shl(mut self, rhs: Move) -> Self::Output {
+        self <<= rhs;
+        self
+    }
+}
+
+impl<R: PrimUInt> ShlAssign<Move> for DynBitBoard<R> {
+    fn shl_assign(&mut self, rhs: Move) {
+        let shape = self.shape();
+        shift_move(&mut self.blocks, shape, rhs);
+    }
+}
+
+impl<R: PrimUInt> BitAnd for &DynBitBoard<R> {
+    type Output = DynBitBoard<R>;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result.zip(rhs, and_kernel);
+        result
+    }
+}
+
+impl<R: PrimUInt> BitAndAssign<&Self> for DynBitBoard<R> {
+    fn bitand_assign(&mut self, rhs: &Self) {
+        self.zip(rhs, and_kernel);
+    }
+}
+
+impl<R: PrimUInt> BitOr for &DynBitBoard<R> {
+    type Output = DynBitBoard<R>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result.zip(rhs, or_kernel);
+        result
+    }
+}
+
+impl<R: PrimUInt> BitOrAssign<&Self> for DynBitBoard<R> {
+    fn bitor_assign(&mut self, rhs: &Self) {
+        self.zip(rhs, or_kernel);
+    }
+}
+
+impl<R: PrimUInt> BitXor for &DynBitBoard<R> {
+    type Output = DynBitBoard<R>;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result.zip(rhs, xor_kernel);
+        result
+    }
+}
+
+impl<R: PrimUInt> BitXorAssign<&Self> for DynBitBoard<R> {
+    fn bitxor_assign(&mut self, rhs: &Self) {
+        self.zip(rhs, xor_kernel);
+    }
+}
+
+impl<R: PrimUInt> Not for &DynBitBoard<R> {
+    type Output = DynBitBoard<R>;
+
+    fn not(self) -> Self::Output {
+        !self.clone()
+    }
+}
+
+impl<R: PrimUInt> Not for DynBitBoard<R> {
+    type Output = Self;
+
+    fn not(mut self) -> Self::Output {
+        self.blocks.iter_mut().for_each(|block| *block = !*block);
+        if let Some(block) = self.blocks.last_mut() {
+            *block &= last_block_mask(self.size * self.size);
+        }
+        self
+    }
+}
+
+impl<R: PrimUInt> Display for DynBitBoard<R> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write_grid(f, self.size, |x, y| self.is_set(x, y))
+    }
+}
+
+impl<R: PrimUInt> Mask for DynBitBoard<R> {
+    fn transform(&self, t: Isometry) -> Self {
+        let mut result = DynBitBoard::empty(self.size);
+        for y in 0..self.size {
+            for x in (0..self.size).filter(|&x| self.is_set(x, y)) {
+                let (tx, ty) = t.cell((x, y), self.size);
+                result.set(tx, ty);
+            }
+        }
+        result
+    }
+
+    fn intersect(&self, other: &Self) -> Self {
+        self & other
+    }
+
+    fn same_cells(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl<R: PrimUInt> MaskedMove<DynBitBoard<R>> {
+    fn apply(&self, board: &DynBitBoard<R>) -> DynBitBoard<R> {
+        let mut result = match &self.from {
+            Some(from) => &(board & from) << self.step,
+            None => board << self.step,
+        };
+        if let Some(to) = &self.to {
+            result &= to;
+        }
+        result
+    }
+}
+
+// The Moves builder for DynBitBoards
+pub struct DynMoves<'a, R: PrimUInt> {
+    from: &'a DynBitBoard<R>,
+
+    moves: Vec<MaskedMove<DynBitBoard<R>>>,
+
+    open: Option<Move>,
+}
+
+impl<'a, R: PrimUInt> DynMoves<'a, R> {
+    pub fn new(from: &'a DynBitBoard<R>) -> Self {
+        DynMoves {
+            from,
+            moves: Vec::new(),
+            open: None,
+        }
+    }
+
+    pub fn translate(mut self, m: Move) -> Self {
+        self.moves.push(m.into());
+        self.open = None;
+        self
+    }
+
+    // Limits the last move to source cells in the mask, as Moves::from_mask does. The mask
+    // must be the same size as the board.
+    pub fn from_mask(mut self, mask: DynBitBoard<R>) -> Self {
+        mask_last(&mut self.moves, mask, |m| &mut m.from);
+        self.open = None;
+        self
+    }
+
+    // Limits the last move to destinations in the mask, like from_mask
+    pub fn to_mask(mut self, mask: DynBitBoard<R>) -> Self {
+        mask_last(&mut self.moves, mask, |m| &mut m.to);
+        self.open = None;
+        self
+    }
+
+    pub fn left(self, i: usize) -> Self {
+        self.step(Move::Left(i))
+    }
+
+    pub fn right(self, i: usize) -> Self {
+        self.step(Move::Right(i))
+    }
+
+    pub fn up(self, i: usize) -> Self {
+        self.step(Move::Up(i))
+    }
+
+    pub fn down(self, i: usize) -> Self {
+        self.step(Move::Down(i))
+    }
+
+    pub fn repeat(mut self, i: usize) -> Self {
+        match self.open {
+            Some(_) => repeat_last(&mut self.moves, i),
+            None => repeat_moves(&mut self.moves, i),
+        }
+        self.open = None;
+        self
+    }
+
+    pub fn rotate(mut self, r: Rotation) -> Self {
+        rotate_moves(&mut self.moves, r);
+        self.open = None;
+        self
+    }
+
+    pub fn mirror(mut self) -> Self {
+        mirror_moves(&mut self.moves);
+        self.open = None;
+        self
+    }
+
+    pub fn reflect(mut self, r: Reflection) -> Self {
+        reflect_moves(&mut self.moves, r);
+        self.open = None;
+        self
+    }
+
+    pub fn symmetrize(mut self, group: Symmetry) -> Self {
+        symmetrize_moves(&mut self.moves, group);
+        self.open = None;
+        self
+    }
+
+    fn step(mut self, m: Move) -> Self {
+        step_moves(&mut self.moves, &mut self.open, m);
+        self
+    }
+
+    pub fn collect(self) -> DynBitBoard<R> {
+        let bytes = self.from.blocks.len() * mem::size_of::<R>();
+        union_moves(
+            &self.moves,
+            self.from.parallel(),
+            bytes,
+            |moves| self.union_of(moves),
+            |a, b| &a | &b,
+        )
+    }
+
+    fn union_of(&self, moves: &[MaskedMove<DynBitBoard<R>>]) -> DynBitBoard<R> {
+        let mut result = DynBitBoard::empty(self.from.size);
+        for m in moves {
+            result |= &m.apply(self.from);
+        }
+        result
+    }
+}
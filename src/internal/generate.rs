@@ -0,0 +1,69 @@
+use super::moves::MaskedMove;
+use super::*;
+
+// Moves worked out separately for each set cell of the source board, so it's known which
+// piece reaches which cell. Built with Moves::by_source.
+pub struct SourceMoves<'a, N: Unsigned, R: PrimUInt> {
+    from: &'a BitBoard<N, R>,
+    moves: Vec<MaskedMove<BitBoard<N, R>>>,
+    // Cells destinations are allowed on, when the friendly or enemy masks have been given
+    targets: Option<BitBoard<N, R>>,
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> Moves<'a, N, R> {
+    pub fn by_source(self) -> SourceMoves<'a, N, R> {
+        SourceMoves {
+            from: self.from,
+            moves: self.moves,
+            targets: None,
+        }
+    }
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> SourceMoves<'a, N, R> {
+    // Drops destinations occupied by our own pieces
+    pub fn friendly(mut self, mask: &BitBoard<N, R>) -> Self {
+        let open = !mask;
+        self.targets = Some(match self.targets {
+            Some(targets) => &targets & &open,
+            None => open,
+        });
+        self
+    }
+
+    // Keeps only destinations occupied by the enemy, i.e. captures
+    pub fn enemy(mut self, mask: &BitBoard<N, R>) -> Self {
+        self.targets = Some(match self.targets {
+            Some(targets) => &targets & mask,
+            None => mask.clone(),
+        });
+        self
+    }
+
+    // Cells reachable from (x, y), whether or not it's set in the source board
+    pub fn destinations_from(&self, x: usize, y: usize) -> BitBoard<N, R> {
+        let single = BitBoard::new(vec![(x, y)]);
+        let mut result = BitBoard::default();
+        for m in &self.moves {
+            result |= &m.apply(&single);
+        }
+
+        if let Some(targets) = &self.targets {
+            result &= targets;
+        }
+        result
+    }
+
+    // Each source cell with the board of cells it reaches
+    pub fn destinations(&self) -> impl Iterator<Item = ((usize, usize), BitBoard<N, R>)> + '_ {
+        self.from
+            .ones()
+            .map(move |(x, y)| ((x, y), self.destinations_from(x, y)))
+    }
+
+    // Every (from, to) move, grouped by source cell
+    pub fn pairs(&self) -> impl Iterator<Item = ((usize, usize), (usize, usize))> + '_ {
+        self.destinations()
+            .flat_map(|(from, to)| to.ones().map(move |to| (from, to)).collect::<Vec<_>>())
+    }
+}
@@ -0,0 +1,198 @@
+use super::*;
+use std::fmt::{Display, Formatter};
+use std::ops::{Shl, ShlAssign};
+
+// The six neighbours of a hex cell.
+// Axial coordinates (q, r) map straight onto (x, y), which lays the hex grid out as a
+// rhombus where each row is offset half a cell to the right of the one below it.
+// The axial q axis is then x, the r axis is y, and the third axis runs along the
+// UpLeft/DownRight diagonal.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HexDirection {
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+impl HexDirection {
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::East,
+        HexDirection::NorthEast,
+        HexDirection::NorthWest,
+        HexDirection::West,
+        HexDirection::SouthWest,
+        HexDirection::SouthEast,
+    ];
+
+    // Axial offset (dq, dr) of the neighbour in this direction
+    pub fn offset(self) -> (isize, isize) {
+        use HexDirection::*;
+        match self {
+            East => (1, 0),
+            West => (-1, 0),
+            NorthEast => (0, 1),
+            NorthWest => (-1, 1),
+            SouthEast => (1, -1),
+            SouthWest => (0, -1),
+        }
+    }
+
+    pub fn to_move(self) -> Move {
+        use HexDirection::*;
+        match self {
+            East => Move::Right(1),
+            West => Move::Left(1),
+            NorthEast => Move::Up(1),
+            NorthWest => Move::UpLeft(1, 1),
+            SouthEast => Move::DownRight(1, 1),
+            SouthWest => Move::Down(1),
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        use HexDirection::*;
+        match self {
+            East => West,
+            West => East,
+            NorthEast => SouthWest,
+            NorthWest => SouthEast,
+            SouthEast => NorthWest,
+            SouthWest => NorthEast,
+        }
+    }
+}
+
+impl From<HexDirection> for Move {
+    fn from(d: HexDirection) -> Self {
+        d.to_move()
+    }
+}
+
+// An N sided rhombus of hex cells, stored in an NxN BitBoard
+#[derive(Clone, Debug, Default)]
+pub struct HexBoard<N: Unsigned, R: PrimUInt = u64> {
+    board: BitBoard<N, R>,
+}
+
+impl<N: Unsigned, R: PrimUInt> HexBoard<N, R> {
+    pub fn new(initial: Vec<(usize, usize)>) -> Self {
+        HexBoard {
+            board: BitBoard::new(initial),
+        }
+    }
+
+    pub fn set(&mut self, q: usize, r: usize) {
+        self.board.set(q, r);
+    }
+
+    pub fn unset(&mut self, q: usize, r: usize) {
+        self.board.unset(q, r);
+    }
+
+    pub fn is_set(&self, q: usize, r: usize) -> bool {
+        self.board.is_set(q, r)
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.board.count_ones()
+    }
+
+    pub fn board(&self) -> &BitBoard<N, R> {
+        &self.board
+    }
+
+    pub fn into_board(self) -> BitBoard<N, R> {
+        self.board
+    }
+
+    // All cells adjacent to a set cell, not including the set cells themselves
+    pub fn neighbours(&self) -> Self {
+        let empty = !&self.board;
+        let mut board = Self::adjacent(&self.board);
+        board &= &empty;
+        HexBoard { board }
+    }
+
+    // Grows the set cells through `mask` until nothing more can be reached, giving every cell
+    // of `mask` connected to a set cell. Set cells outside of `mask` are dropped.
+    // This is the connection check for games like Hex and Havannah.
+    pub fn flood_fill(&self, mask: &Self) -> Self {
+        let mut filled = &self.board & &mask.board;
+        loop {
+            let mut grown = Self::adjacent(&filled);
+            grown &= &mask.board;
+            grown |= &filled;
+
+            if grown.count_ones() == filled.count_ones() {
+                return HexBoard { board: grown };
+            }
+            filled = grown;
+        }
+    }
+
+    // Union of the board moved one cell in each of the six directions
+    fn adjacent(board: &BitBoard<N, R>) -> BitBoard<N, R> {
+        HexDirection::ALL
+            .iter()
+            .fold(board.moves(), |moves, d| moves.translate(d.to_move()))
+            .collect()
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> From<BitBoard<N, R>> for HexBoard<N, R> {
+    fn from(board: BitBoard<N, R>) -> Self {
+        HexBoard { board }
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Shl<HexDirection> for &HexBoard<N, R> {
+    type Output = HexBoard<N, R>;
+
+    fn shl(self, rhs: HexDirection) -> Self::Output {
+        HexBoard {
+            board: &self.board << rhs.to_move(),
+        }
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Shl<HexDirection> for HexBoard<N, R> {
+    type Output = Self;
+
+    fn shl(self, rhs: HexDirection) -> Self::Output {
+        HexBoard {
+            board: self.board << rhs.to_move(),
+        }
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> ShlAssign<HexDirection> for HexBoard<N, R> {
+    fn shl_assign(&mut self, rhs: HexDirection) {
+        self.board <<= rhs.to_move();
+    }
+}
+
+// Prints the rhombus top-down, indenting each row by half a cell per row so neighbouring
+// cells line up the way they do on a hex grid
+impl<N: Unsigned, R: PrimUInt> Display for HexBoard<N, R> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let s = N::USIZE;
+
+        for c in 0..s {
+            let r = s - c - 1;
+            write!(f, "{:indent$}", "", indent = r)?;
+            for q in 0..s {
+                if self.is_set(q, r) {
+                    write!(f, "1 ")?;
+                } else {
+                    write!(f, "0 ")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        write!(f, "")
+    }
+}
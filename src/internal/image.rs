@@ -0,0 +1,225 @@
+use super::*;
+use std::fmt::{Display, Formatter, Write as FmtWrite};
+use std::io::{self, Read, Write};
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum PbmFormat {
+    // P1, rows of 0s and 1s
+    Plain,
+    // P4, rows packed 8 pixels to a byte
+    Binary,
+}
+
+#[derive(Debug)]
+pub enum PbmError {
+    Io(io::Error),
+    // Not a P1 or P4 file
+    BadMagic,
+    BadHeader,
+    SizeMismatch {
+        expected: usize,
+        width: usize,
+        height: usize,
+    },
+    BadPixel(char),
+    Truncated,
+}
+
+impl Display for PbmError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            PbmError::Io(e) => write!(f, "io error: {}", e),
+            PbmError::BadMagic => write!(f, "not a P1 or P4 bitmap"),
+            PbmError::BadHeader => write!(f, "malformed bitmap header"),
+            PbmError::SizeMismatch {
+                expected,
+                width,
+                height,
+            } => write!(
+                f,
+                "expected a {e}x{e} bitmap, found {}x{}",
+                width,
+                height,
+                e = expected
+            ),
+            PbmError::BadPixel(c) => write!(f, "unexpected pixel {:?}", c),
+            PbmError::Truncated => write!(f, "bitmap ends early"),
+        }
+    }
+}
+
+impl std::error::Error for PbmError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PbmError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PbmError {
+    fn from(e: io::Error) -> Self {
+        PbmError::Io(e)
+    }
+}
+
+// Netpbm bitmaps, where a black pixel is a set cell. The first row of the image is the top
+// of the board, the same way round as Display.
+impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
+    pub fn write_pbm<W: Write>(&self, mut w: W, format: PbmFormat) -> io::Result<()> {
+        let s = N::USIZE;
+        let rows = (0..s).rev();
+
+        match format {
+            PbmFormat::Plain => {
+                writeln!(w, "P1\n{} {}", s, s)?;
+                for y in rows {
+                    // Lines are kept under the 70 characters the format allows
+                    for chunk in (0..s).collect::<Vec<_>>().chunks(35) {
+                        let line: Vec<&str> = chunk
+                            .iter()
+                            .map(|&x| if self.is_set(x, y) { "1" } else { "0" })
+                            .collect();
+                        writeln!(w, "{}", line.join(" "))?;
+                    }
+                }
+            }
+            PbmFormat::Binary => {
+                write!(w, "P4\n{} {}\n", s, s)?;
+                let mut row = vec![0u8; s.div_ceil(8)];
+                for y in rows {
+                    row.iter_mut().for_each(|b| *b = 0);
+                    (0..s)
+                        .filter(|&x| self.is_set(x, y))
+                        .for_each(|x| row[x / 8] |= 0x80 >> (x % 8));
+                    w.write_all(&row)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read_pbm<Rd: Read>(mut r: Rd) -> Result<Self, PbmError> {
+        let mut data = Vec::new();
+        r.read_to_end(&mut data)?;
+
+        let format = match data.get(..2) {
+            Some(b"P1") => PbmFormat::Plain,
+            Some(b"P4") => PbmFormat::Binary,
+            _ => return Err(PbmError::BadMagic),
+        };
+
+        let mut pos = 2;
+        let width = header_number(&data, &mut pos)?;
+        let height = header_number(&data, &mut pos)?;
+        let s = N::USIZE;
+        if width != s || height != s {
+            return Err(PbmError::SizeMismatch {
+                expected: s,
+                width,
+                height,
+            });
+        }
+
+        let mut board = Self::default();
+        match format {
+            PbmFormat::Plain => {
+                for i in 0..s * s {
+                    skip_space(&data, &mut pos);
+                    match data.get(pos) {
+                        Some(b'1') => board.set(i % s, s - i / s - 1),
+                        Some(b'0') => (),
+                        Some(&c) => return Err(PbmError::BadPixel(c as char)),
+                        None => return Err(PbmError::Truncated),
+                    }
+                    pos += 1;
+                }
+            }
+            PbmFormat::Binary => {
+                // A single whitespace character separates the header from the pixels
+                pos += 1;
+                let row_bytes = s.div_ceil(8);
+                let pixels = data.get(pos..pos + row_bytes * s);
+                let pixels = pixels.ok_or(PbmError::Truncated)?;
+
+                for (r, row) in pixels.chunks(row_bytes).enumerate() {
+                    (0..s)
+                        .filter(|&x| row[x / 8] & (0x80 >> (x % 8)) != 0)
+                        .for_each(|x| board.set(x, s - r - 1));
+                }
+            }
+        }
+        Ok(board)
+    }
+
+    // An SVG image of the board, cell_size pixels to a cell
+    pub fn to_svg(&self, cell_size: usize) -> String {
+        self.renderer().to_svg(cell_size)
+    }
+}
+
+// Skips whitespace and # comments
+fn skip_space(data: &[u8], pos: &mut usize) {
+    while let Some(&c) = data.get(*pos) {
+        match c {
+            b'#' => {
+                while data.get(*pos).is_some_and(|&c| c != b'\n') {
+                    *pos += 1;
+                }
+            }
+            c if c.is_ascii_whitespace() => *pos += 1,
+            _ => return,
+        }
+    }
+}
+
+fn header_number(data: &[u8], pos: &mut usize) -> Result<usize, PbmError> {
+    skip_space(data, pos);
+    let start = *pos;
+    while data.get(*pos).is_some_and(|c| c.is_ascii_digit()) {
+        *pos += 1;
+    }
+
+    std::str::from_utf8(&data[start..*pos])
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .ok_or(PbmError::BadHeader)
+}
+
+// Cells are filled with the colour of their glyph, black when it has none, and empty cells
+// with the colour of the empty glyph, white when it has none. Labels and symbols aren't drawn.
+impl<'a, N: Unsigned, R: PrimUInt> Renderer<'a, N, R> {
+    pub fn to_svg(&self, cell_size: usize) -> String {
+        let s = N::USIZE;
+        let side = s * cell_size;
+        let mut svg = String::new();
+
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\">",
+            side
+        );
+        for row in 0..s {
+            let y = match self.origin {
+                Origin::BottomLeft => s - row - 1,
+                Origin::TopLeft => row,
+            };
+            for x in 0..s {
+                let fill = match self.overlay_at(x, y) {
+                    Some(glyph) => glyph.colour.map_or("black", Colour::css_name),
+                    None => self.empty.colour.map_or("white", Colour::css_name),
+                };
+                let _ = writeln!(
+                    svg,
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" fill=\"{}\" stroke=\"grey\"/>",
+                    x * cell_size,
+                    row * cell_size,
+                    fill,
+                    c = cell_size
+                );
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
@@ -1,4 +1,6 @@
-use super::{BitBoard, PrimUInt};
+use super::moves::MaskedMove;
+use super::{BitBoard, Move, Moves, PrimUInt};
+use std::marker::PhantomData;
 use typenum::Unsigned;
 
 impl<'a, N: Unsigned, R: PrimUInt> IntoIterator for &'a BitBoard<N, R> {
@@ -116,3 +118,99 @@ impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
         BlockIterMut { start, end }
     }
 }
+
+impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
+    // The (x, y) of every set cell, lowest first
+    pub fn ones(&self) -> SetCells<'_, N, R> {
+        let blocks = self.blocks();
+        SetCells {
+            blocks,
+            block: 0,
+            current: blocks.first().copied().unwrap_or_else(R::zero),
+            _typenum: PhantomData,
+        }
+    }
+}
+
+// Jumps straight from one set bit to the next rather than testing every cell
+pub struct SetCells<'a, N: Unsigned, R: PrimUInt = u64> {
+    blocks: &'a [R],
+    block: usize,
+    current: R,
+    _typenum: PhantomData<N>,
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> Iterator for SetCells<'a, N, R> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current == R::zero() {
+            self.block += 1;
+            self.current = *self.blocks.get(self.block)?;
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        self.current = self.current & (self.current - R::one());
+        let pos = self.block * BitBoard::<N, R>::BLOCK_SIZE_BITS + bit;
+        Some((pos % N::USIZE, pos / N::USIZE))
+    }
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> IntoIterator for Moves<'a, N, R> {
+    type Item = (Move, BitBoard<N, R>);
+    type IntoIter = MovesIter<'a, N, R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        MovesIter {
+            from: self.from,
+            moves: self.moves.into_iter(),
+        }
+    }
+}
+
+// Each move of the builder with the board it shifts to, worked out one at a time so callers
+// can stop early instead of paying for collect
+pub struct MovesIter<'a, N: Unsigned, R: PrimUInt = u64> {
+    from: &'a BitBoard<N, R>,
+    moves: std::vec::IntoIter<MaskedMove<BitBoard<N, R>>>,
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> Iterator for MovesIter<'a, N, R> {
+    type Item = (Move, BitBoard<N, R>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let m = self.moves.next()?;
+        Some((m.step, m.apply(self.from)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.moves.size_hint()
+    }
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> Moves<'a, N, R> {
+    // The moves that keep at least one set cell on the board
+    pub fn live(&self) -> LiveMoves<'_, N, R> {
+        LiveMoves {
+            from: self.from,
+            moves: self.moves.iter(),
+        }
+    }
+}
+
+// Checks each move against the set cells directly, so no boards get allocated
+pub struct LiveMoves<'a, N: Unsigned, R: PrimUInt = u64> {
+    from: &'a BitBoard<N, R>,
+    moves: std::slice::Iter<'a, MaskedMove<BitBoard<N, R>>>,
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> Iterator for LiveMoves<'a, N, R> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let from = self.from;
+        self.moves
+            .find(|m| from.ones().any(|cell| m.reaches(cell)))
+            .map(|m| m.step)
+    }
+}
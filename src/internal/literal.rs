@@ -0,0 +1,93 @@
+// Support for the bitboard! macro. It's public so the macro can reach it from other crates,
+// but isn't meant to be used directly.
+use super::*;
+
+pub use typenum::U;
+
+// Builds a board literal from rows of cells, top-down. Cells are x, X, # or 1 when set and
+// . or 0 when empty, with optional spaces between them.
+// The board size is checked against the grid at compile time, and is worked out from the
+// grid when the block type is given first.
+//
+//     let glider: BitBoard<U5, u32> = bitboard![
+//         ". x . . .",
+//         ". . x . .",
+//         "x x x . .",
+//         ". . . . .",
+//         ". . . . .",
+//     ];
+//     let blinker = bitboard![u16; "...", "xxx", "..."];
+#[macro_export]
+macro_rules! bitboard {
+    ($block:ty; $($row:literal),+ $(,)?) => {{
+        const ROWS: &[&str] = &[$($row),+];
+        const WIDTH: usize = $crate::literal::grid_width(ROWS);
+        $crate::literal::board::<$crate::literal::U<{ ROWS.len() }>, $block, { ROWS.len() }, WIDTH>(
+            ROWS,
+        )
+    }};
+    ($($row:literal),+ $(,)?) => {{
+        const ROWS: &[&str] = &[$($row),+];
+        const WIDTH: usize = $crate::literal::grid_width(ROWS);
+        $crate::literal::board::<_, _, { ROWS.len() }, WIDTH>(ROWS)
+    }};
+}
+
+pub fn board<N: Unsigned, R: PrimUInt, const H: usize, const W: usize>(
+    rows: &[&str],
+) -> BitBoard<N, R> {
+    const {
+        assert!(
+            H == N::USIZE && W == N::USIZE,
+            "bitboard! grid doesn't match the board size"
+        )
+    };
+
+    let mut result = BitBoard::default();
+    for (r, row) in rows.iter().enumerate() {
+        let y = H - r - 1;
+        row.bytes()
+            .filter(|b| !b.is_ascii_whitespace())
+            .enumerate()
+            .filter(|(_, b)| is_set_cell(*b))
+            .for_each(|(x, _)| result.set(x, y));
+    }
+    result
+}
+
+// Width shared by every row, failing to compile if they differ or hold anything but cells
+pub const fn grid_width(rows: &[&str]) -> usize {
+    let width = row_width(rows[0]);
+    let mut i = 1;
+    while i < rows.len() {
+        assert!(
+            row_width(rows[i]) == width,
+            "bitboard! rows must all be the same width"
+        );
+        i += 1;
+    }
+    width
+}
+
+const fn row_width(row: &str) -> usize {
+    let bytes = row.as_bytes();
+    let mut width = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if is_set_cell(b) || b == b'.' || b == b'0' {
+            width += 1;
+        } else {
+            assert!(
+                b.is_ascii_whitespace(),
+                "bitboard! cells must be one of x X # 1 . 0"
+            );
+        }
+        i += 1;
+    }
+    width
+}
+
+const fn is_set_cell(b: u8) -> bool {
+    matches!(b, b'x' | b'X' | b'#' | b'1')
+}
@@ -9,6 +9,7 @@ pub type BitBoard5x5 = BitBoard<U5, u32>;
 pub type BitBoard6x6 = BitBoard<U6, u64>;
 pub type BitBoard7x7 = BitBoard<U7, u64>;
 pub type BitBoard8x8 = BitBoard<U8, u64>;
+pub type BitBoard11x11 = BitBoard<U11, u128>;
 
 pub trait PrimUInt:
     PrimInt
@@ -24,6 +25,8 @@ pub trait PrimUInt:
     + ShrAssign
     + BitXor
     + BitXorAssign
+    + Send
+    + Sync
 {
 }
 
@@ -32,16 +35,57 @@ impl PrimUInt for u16 {}
 impl PrimUInt for u32 {}
 impl PrimUInt for u64 {}
 
-// u128 alignment bug: https://github.com/rust-lang/rust/issues/54341
-// impl PrimUInt for u128 {}
+// u128 used to be 8 byte aligned on x86_64 ( https://github.com/rust-lang/rust/issues/54341 ),
+// so block counts are worked out from size_of and the alignment only goes into the layout
+impl PrimUInt for u128 {}
 
+mod attack;
+mod betza;
+mod binary;
 mod board;
+mod const_board;
+mod convert;
+mod cube;
+mod dynamic;
+mod generate;
+mod hex;
+mod image;
 mod iter;
+#[doc(hidden)]
+pub mod literal;
 mod moves;
+mod notation;
 mod ops;
+mod parallel;
+mod parse;
+mod pattern;
+mod render;
+#[cfg(feature = "serde")]
+pub(crate) mod serialize;
 mod shift;
+#[cfg(feature = "simd")]
+pub(crate) mod simd;
+mod stack;
 mod statics;
 
+pub use attack::AttackTable;
+pub use betza::{Betza, BetzaError, Ray};
+pub use binary::{BinaryError, FORMAT_VERSION};
 pub use board::BitBoard;
-pub use iter::BitBoardIter;
+pub use const_board::*;
+pub use convert::BlocksError;
+pub use cube::{BitBoard3D, Depth, Move3D};
+pub use dynamic::{DynBitBoard, DynMoves, SizeMismatch};
+pub use generate::SourceMoves;
+pub use hex::{HexBoard, HexDirection};
+pub use image::{PbmError, PbmFormat};
+pub use iter::{BitBoardIter, LiveMoves, MovesIter, SetCells};
 pub use moves::*;
+pub use notation::{parse_moves, Coord, NotationError};
+pub use parse::{ParseError, ParseErrorKind};
+pub use pattern::MovePattern;
+pub use render::{Colour, Glyph, Labels, Origin, Renderer};
+use shift::{shift_move, Shape};
+pub use stack::BitBoardStack;
+use statics::last_block_mask;
+pub use statics::required_blocks_for;
@@ -1,6 +1,7 @@
 use super::*;
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Move {
     NullMove,
     Left(usize),
@@ -14,10 +15,123 @@ pub enum Move {
     DownRight(usize, usize),
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rotation {
     Clockwise,
     AntiClockwise,
+    HalfTurn,
+}
+
+// The axis a move is reflected across. Diagonal runs bottom left to top right, and
+// AntiDiagonal top left to bottom right.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Reflection {
+    Vertical,
+    Horizontal,
+    Diagonal,
+    AntiDiagonal,
+}
+
+// Symmetry groups of the square, for filling in the moves of a piece from some of them.
+// Each group holds the identity along with the listed transformations.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Symmetry {
+    // A half turn, the same as mirror
+    HalfTurn,
+    // All three turns
+    Rotational,
+    // Reflection across the vertical axis, i.e left-right symmetric like a shogi gold
+    Vertical,
+    Horizontal,
+    Diagonal,
+    AntiDiagonal,
+    // Reflections across both the vertical and horizontal axes, and so a half turn
+    Orthogonal,
+    // Every turn and reflection, like a knight
+    Full,
+}
+
+impl Symmetry {
+    // Where the group sends a move, the move itself first
+    pub fn images(self, m: Move) -> Vec<Move> {
+        self.isometries().into_iter().map(|t| t.apply(m)).collect()
+    }
+
+    pub(super) fn isometries(self) -> Vec<Isometry> {
+        use Isometry::*;
+        let turns = [
+            Turn(Rotation::Clockwise),
+            Turn(Rotation::HalfTurn),
+            Turn(Rotation::AntiClockwise),
+        ];
+        let reflections = [
+            Reflect(Reflection::Vertical),
+            Reflect(Reflection::Horizontal),
+            Reflect(Reflection::Diagonal),
+            Reflect(Reflection::AntiDiagonal),
+        ];
+        let mut group = vec![Identity];
+        match self {
+            Symmetry::HalfTurn => group.push(Turn(Rotation::HalfTurn)),
+            Symmetry::Rotational => group.extend_from_slice(&turns),
+            Symmetry::Vertical => group.push(Reflect(Reflection::Vertical)),
+            Symmetry::Horizontal => group.push(Reflect(Reflection::Horizontal)),
+            Symmetry::Diagonal => group.push(Reflect(Reflection::Diagonal)),
+            Symmetry::AntiDiagonal => group.push(Reflect(Reflection::AntiDiagonal)),
+            Symmetry::Orthogonal => group.extend_from_slice(&[
+                Reflect(Reflection::Vertical),
+                Reflect(Reflection::Horizontal),
+                Turn(Rotation::HalfTurn),
+            ]),
+            Symmetry::Full => {
+                group.extend_from_slice(&turns);
+                group.extend_from_slice(&reflections);
+            }
+        }
+        group
+    }
+}
+
+// One of the eight ways of turning or flipping the board, so masks can be moved along with
+// the moves they're attached to
+#[derive(Copy, Clone, Debug)]
+pub(super) enum Isometry {
+    Identity,
+    Turn(Rotation),
+    Reflect(Reflection),
+}
+
+impl Isometry {
+    pub(super) fn apply(self, m: Move) -> Move {
+        match self {
+            Isometry::Identity => m,
+            Isometry::Turn(r) => m.rotate(r),
+            Isometry::Reflect(r) => m.reflect(r),
+        }
+    }
+
+    // Where a cell of an s by s board ends up, turning or flipping about the middle of the
+    // board the same way moves are turned about the origin
+    pub(super) fn cell(self, (x, y): (usize, usize), s: usize) -> (usize, usize) {
+        let e = s - 1;
+        match self {
+            Isometry::Identity => (x, y),
+            Isometry::Turn(Rotation::Clockwise) => (y, e - x),
+            Isometry::Turn(Rotation::AntiClockwise) => (e - y, x),
+            Isometry::Turn(Rotation::HalfTurn) => (e - x, e - y),
+            Isometry::Reflect(Reflection::Vertical) => (e - x, y),
+            Isometry::Reflect(Reflection::Horizontal) => (x, e - y),
+            Isometry::Reflect(Reflection::Diagonal) => (y, x),
+            Isometry::Reflect(Reflection::AntiDiagonal) => (e - y, e - x),
+        }
+    }
+
+    pub(super) fn board<N: Unsigned, R: PrimUInt>(self, board: &BitBoard<N, R>) -> BitBoard<N, R> {
+        BitBoard::new(board.ones().map(|c| self.cell(c, N::USIZE)).collect())
+    }
 }
 
 use Move::*;
@@ -52,18 +166,56 @@ impl Move {
         }
     }
 
+    // The (x, y) distance the move covers, with up and right positive
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            NullMove => (0, 0),
+            Left(i) => (-(i as isize), 0),
+            Right(i) => (i as isize, 0),
+            Up(i) => (0, i as isize),
+            Down(i) => (0, -(i as isize)),
+            UpLeft(u, l) => (-(l as isize), u as isize),
+            UpRight(u, r) => (r as isize, u as isize),
+            DownLeft(d, l) => (-(l as isize), -(d as isize)),
+            DownRight(d, r) => (r as isize, -(d as isize)),
+        }
+    }
+
+    // The move covering an (x, y) distance. Straight lines always come out as Left, Right,
+    // Up or Down rather than a diagonal with a zero component.
+    pub fn from_offset(x: isize, y: isize) -> Self {
+        let (h, v) = (x.unsigned_abs(), y.unsigned_abs());
+        match (x.signum(), y.signum()) {
+            (0, 0) => NullMove,
+            (-1, 0) => Left(h),
+            (1, 0) => Right(h),
+            (0, 1) => Up(v),
+            (0, -1) => Down(v),
+            (-1, 1) => UpLeft(v, h),
+            (1, 1) => UpRight(v, h),
+            (-1, -1) => DownLeft(v, h),
+            _ => DownRight(v, h),
+        }
+    }
+
+    // Turns the (x, y) offset about the origin, so UpLeft(1, 2) goes clockwise to
+    // UpRight(2, 1) and zero length moves come out as NullMove
     pub fn rotate(self, r: Rotation) -> Self {
-        use Rotation::*;
-        match (self, r) {
-            (Up(i), Clockwise) | (Down(i), AntiClockwise) => Right(i),
-            (Down(i), Clockwise) | (Up(i), AntiClockwise) => Left(i),
-            (Left(i), Clockwise) | (Right(i), AntiClockwise) => Up(i),
-            (Right(i), Clockwise) | (Left(i), AntiClockwise) => Down(i),
-            (UpLeft(i1, i2), Clockwise) | (DownRight(i1, i2), AntiClockwise) => UpRight(i1, i2),
-            (UpRight(i1, i2), Clockwise) | (DownLeft(i1, i2), AntiClockwise) => DownRight(i1, i2),
-            (DownLeft(i1, i2), Clockwise) | (UpRight(i1, i2), AntiClockwise) => UpLeft(i1, i2),
-            (DownRight(i1, i2), Clockwise) | (UpLeft(i1, i2), AntiClockwise) => DownLeft(i1, i2),
-            _ => NullMove,
+        let (x, y) = self.offset();
+        match r {
+            Rotation::Clockwise => Move::from_offset(y, -x),
+            Rotation::AntiClockwise => Move::from_offset(-y, x),
+            Rotation::HalfTurn => Move::from_offset(-x, -y),
+        }
+    }
+
+    pub fn reflect(self, r: Reflection) -> Self {
+        let (x, y) = self.offset();
+        match r {
+            Reflection::Vertical => Move::from_offset(-x, y),
+            Reflection::Horizontal => Move::from_offset(x, -y),
+            Reflection::Diagonal => Move::from_offset(y, x),
+            Reflection::AntiDiagonal => Move::from_offset(-y, -x),
         }
     }
 }
@@ -94,17 +246,150 @@ impl std::ops::Mul<usize> for Move {
 
 impl<'a, N: Unsigned, R: PrimUInt> BitBoard<N, R> {
     pub fn moves(&'a self) -> Moves<'a, N, R> {
-        Moves {
-            from: self,
-            moves: Vec::new(),
-        }
+        Moves::new(self)
     }
 }
 
 pub struct Moves<'a, N: Unsigned, R: PrimUInt> {
-    from: &'a BitBoard<N, R>,
+    pub(super) from: &'a BitBoard<N, R>,
+
+    pub(super) moves: Vec<MaskedMove<BitBoard<N, R>>>,
+
+    // The last left/right/up/down step, while the move it went into can still be extended
+    open: Option<Move>,
+}
+
+// A builder move with the cells it may start from and land on, when they've been limited
+// by from_mask or to_mask. B is the board type the masks are kept as.
+#[derive(Clone)]
+pub(super) struct MaskedMove<B> {
+    pub(super) step: Move,
+    pub(super) from: Option<B>,
+    pub(super) to: Option<B>,
+}
+
+// What the builder transformations need from a mask
+pub(super) trait Mask: Clone {
+    // Turns or flips the cells about the middle of the board
+    fn transform(&self, t: Isometry) -> Self;
+
+    fn intersect(&self, other: &Self) -> Self;
+
+    fn same_cells(&self, other: &Self) -> bool;
+}
+
+impl<N: Unsigned, R: PrimUInt> Mask for BitBoard<N, R> {
+    fn transform(&self, t: Isometry) -> Self {
+        t.board(self)
+    }
+
+    fn intersect(&self, other: &Self) -> Self {
+        self & other
+    }
 
-    moves: Vec<Move>,
+    fn same_cells(&self, other: &Self) -> bool {
+        self.blocks() == other.blocks()
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> MaskedMove<BitBoard<N, R>> {
+    pub(super) fn apply(&self, board: &BitBoard<N, R>) -> BitBoard<N, R> {
+        let mut result = match &self.from {
+            Some(from) => &(board & from) << self.step,
+            None => board << self.step,
+        };
+        if let Some(to) = &self.to {
+            result &= to;
+        }
+        result
+    }
+
+    // Whether the move lands on the board from (x, y), within both masks
+    pub(super) fn reaches(&self, (x, y): (usize, usize)) -> bool {
+        let (dx, dy) = self.step.offset();
+        let (s, tx, ty) = (N::USIZE as isize, x as isize + dx, y as isize + dy);
+        (0..s).contains(&tx)
+            && (0..s).contains(&ty)
+            && self.from.as_ref().is_none_or(|from| from.is_set(x, y))
+            && self
+                .to
+                .as_ref()
+                .is_none_or(|to| to.is_set(tx as usize, ty as usize))
+    }
+}
+
+impl<B> From<Move> for MaskedMove<B> {
+    fn from(step: Move) -> Self {
+        MaskedMove {
+            step,
+            from: None,
+            to: None,
+        }
+    }
+}
+
+// Lets the builder transformations work on plain moves and masked ones alike
+pub(super) trait Step: Clone + From<Move> {
+    fn step(&self) -> Move;
+
+    // Changes the move, leaving any masks as they are
+    fn map(&self, f: impl Fn(Move) -> Move) -> Self;
+
+    // Turns or flips the move, along with any masks
+    fn transform(&self, t: Isometry) -> Self;
+
+    // Whether the move already reaches everything `other` would
+    fn covers(&self, other: &Self) -> bool;
+}
+
+impl Step for Move {
+    fn step(&self) -> Move {
+        *self
+    }
+
+    fn map(&self, f: impl Fn(Move) -> Move) -> Self {
+        f(*self)
+    }
+
+    fn transform(&self, t: Isometry) -> Self {
+        t.apply(*self)
+    }
+
+    fn covers(&self, other: &Self) -> bool {
+        self.offset() == other.offset()
+    }
+}
+
+impl<B: Mask> Step for MaskedMove<B> {
+    fn step(&self) -> Move {
+        self.step
+    }
+
+    fn map(&self, f: impl Fn(Move) -> Move) -> Self {
+        MaskedMove {
+            step: f(self.step),
+            ..self.clone()
+        }
+    }
+
+    fn transform(&self, t: Isometry) -> Self {
+        MaskedMove {
+            step: t.apply(self.step),
+            from: self.from.as_ref().map(|from| from.transform(t)),
+            to: self.to.as_ref().map(|to| to.transform(t)),
+        }
+    }
+
+    fn covers(&self, other: &Self) -> bool {
+        let wider = |a: &Option<B>, b: &Option<B>| match (a, b) {
+            (None, _) => true,
+            (Some(a), Some(b)) => a.same_cells(b),
+            _ => false,
+        };
+        self.step.offset() == other.step.offset()
+            && wider(&self.from, &other.from)
+            && wider(&self.to, &other.to)
+    }
 }
 
 impl<'a, N: Unsigned, R: PrimUInt> Moves<'a, N, R> {
@@ -112,43 +397,219 @@ impl<'a, N: Unsigned, R: PrimUInt> Moves<'a, N, R> {
         Moves::<'a, N, R> {
             from,
             moves: Vec::new(),
+            open: None,
         }
     }
 
     pub fn translate(mut self, m: Move) -> Self {
-        self.moves.push(m);
+        self.moves.push(m.into());
+        self.open = None;
         self
     }
 
+    // Limits the last move to source cells in the mask, e.g. pawn double pushes from the
+    // second rank. Moves made from it by rotate, mirror, reflect and symmetrize get the mask
+    // turned or flipped about the middle of the board the same way, so mirroring a white
+    // pawn's double push gives black's from the seventh rank. repeat keeps it as it is.
+    // Does nothing when there are no moves yet.
+    pub fn from_mask(mut self, mask: BitBoard<N, R>) -> Self {
+        mask_last(&mut self.moves, mask, |m| &mut m.from);
+        self.open = None;
+        self
+    }
+
+    // Limits the last move to destinations in the mask, like from_mask
+    pub fn to_mask(mut self, mask: BitBoard<N, R>) -> Self {
+        mask_last(&mut self.moves, mask, |m| &mut m.to);
+        self.open = None;
+        self
+    }
+
+    // Straight steps collapse into the move before them, so left(1).up(1) is UpLeft(1, 1)
+    // and up(1).left(1).left(1) is UpLeft(1, 2). Stepping back the other way or back along
+    // the first axis of a diagonal starts a fresh move, so up(1).left(2).up(1).right(2) is
+    // the two knight moves UpLeft(1, 2) and UpRight(1, 2). So does any other builder method.
+    pub fn left(self, i: usize) -> Self {
+        self.step(Left(i))
+    }
+
+    pub fn right(self, i: usize) -> Self {
+        self.step(Right(i))
+    }
+
+    pub fn up(self, i: usize) -> Self {
+        self.step(Up(i))
+    }
+
+    pub fn down(self, i: usize) -> Self {
+        self.step(Down(i))
+    }
+
+    // Straight after left/right/up/down this repeats the last move, so left(1).repeat(8)
+    // is a ray of Left(1) .. Left(8). Otherwise every move so far is repeated.
     pub fn repeat(mut self, i: usize) -> Self {
-        let mut new = Vec::with_capacity(self.moves.len() * i + 1);
-        for m in &self.moves {
-            (1..=i).into_iter().for_each(|mul| new.push(m * mul));
+        match self.open {
+            Some(_) => repeat_last(&mut self.moves, i),
+            None => repeat_moves(&mut self.moves, i),
         }
-        new.append(&mut self.moves);
-        self.moves = new;
+        self.open = None;
         self
     }
 
     pub fn rotate(mut self, r: Rotation) -> Self {
-        self.moves.reserve(self.moves.len());
-        self.moves
-            .append(&mut self.moves.iter().map(|m| m.rotate(r)).collect());
+        rotate_moves(&mut self.moves, r);
+        self.open = None;
         self
     }
 
     pub fn mirror(mut self) -> Self {
-        self.moves.reserve(self.moves.len());
-        self.moves
-            .append(&mut self.moves.iter().map(|m| m.mirror()).collect());
+        mirror_moves(&mut self.moves);
+        self.open = None;
+        self
+    }
+
+    pub fn reflect(mut self, r: Reflection) -> Self {
+        reflect_moves(&mut self.moves, r);
+        self.open = None;
+        self
+    }
+
+    // Adds whatever moves are missing for the moves so far to have the given symmetry
+    pub fn symmetrize(mut self, group: Symmetry) -> Self {
+        symmetrize_moves(&mut self.moves, group);
+        self.open = None;
+        self
+    }
+
+    fn step(mut self, m: Move) -> Self {
+        step_moves(&mut self.moves, &mut self.open, m);
         self
     }
 
     pub fn collect(self) -> BitBoard<N, R> {
+        union_moves(
+            &self.moves,
+            BitBoard::<N, R>::PARALLEL,
+            BitBoard::<N, R>::REQUIRED_BYTES,
+            |moves| self.union_of(moves),
+            |a, b| a | b,
+        )
+    }
+
+    fn union_of(&self, moves: &[MaskedMove<BitBoard<N, R>>]) -> BitBoard<N, R> {
         let mut result = BitBoard::default();
-        for m in self.moves {
-            result |= self.from << m;
+        for m in moves {
+            result |= &m.apply(self.from);
         }
         result
     }
 }
+
+// Runs `union` over the moves, split across threads for boards big enough to be worth it.
+// Shared by the collect of both builders, with `board_bytes` the size of one board.
+pub(super) fn union_moves<T: Sync, B: Send>(
+    moves: &[T],
+    parallel: bool,
+    board_bytes: usize,
+    union: impl Fn(&[T]) -> B + Sync,
+    reduce: impl Fn(B, B) -> B,
+) -> B {
+    if parallel {
+        parallel::map_reduce(moves, board_bytes, union, reduce)
+    } else {
+        union(moves)
+    }
+}
+
+// The transformations behind the builder methods, shared with DynMoves and MovePattern
+pub(super) fn step_moves<T: Step>(moves: &mut Vec<T>, open: &mut Option<Move>, m: Move) {
+    // A step carries on the last move unless it goes back against it or turns back to the
+    // first axis of a diagonal. The move is rebuilt from the offsets so a zero length step
+    // can't leave a diagonal with a zero component.
+    let same_way = |a: isize, b: isize| a == 0 || b == 0 || (a > 0) == (b > 0);
+    let horizontal = |s: Move| matches!(s, Left(_) | Right(_));
+    let (x2, y2) = m.offset();
+    let zero = (x2, y2) == (0, 0);
+
+    if let (Some(last), Some(prev)) = (moves.last_mut(), *open) {
+        let (x1, y1) = last.step().offset();
+        let straight = x1 == 0 || y1 == 0;
+        if same_way(x1, x2)
+            && same_way(y1, y2)
+            && (zero || straight || horizontal(prev) == horizontal(m))
+        {
+            *last = last.map(|_| Move::from_offset(x1 + x2, y1 + y2));
+            // A zero length step has no axis, so the one before it still decides
+            if !zero {
+                *open = Some(m);
+            }
+            return;
+        }
+    }
+    moves.push(m.into());
+    *open = Some(m);
+}
+
+// Narrows one of the last move's masks, picked by `field`, to the cells in `mask`
+pub(super) fn mask_last<B: Mask>(
+    moves: &mut [MaskedMove<B>],
+    mask: B,
+    field: impl Fn(&mut MaskedMove<B>) -> &mut Option<B>,
+) {
+    if let Some(last) = moves.last_mut() {
+        let current = field(last);
+        *current = Some(match current.take() {
+            Some(current) => current.intersect(&mask),
+            None => mask,
+        });
+    }
+}
+
+// Replaces the last move with its multiples from 1 to i
+pub(super) fn repeat_last<T: Step>(moves: &mut Vec<T>, i: usize) {
+    if let Some(last) = moves.pop() {
+        (1..=i).for_each(|mul| moves.push(last.map(|s| s * mul)));
+    }
+}
+
+pub(super) fn repeat_moves<T: Step>(moves: &mut Vec<T>, i: usize) {
+    let mut new = Vec::with_capacity(moves.len() * i + 1);
+    for m in moves.iter() {
+        (1..=i).for_each(|mul| new.push(m.map(|s| s * mul)));
+    }
+    new.append(moves);
+    *moves = new;
+}
+
+pub(super) fn rotate_moves<T: Step>(moves: &mut Vec<T>, r: Rotation) {
+    transform_moves(moves, Isometry::Turn(r));
+}
+
+// mirror is the same as a half turn
+pub(super) fn mirror_moves<T: Step>(moves: &mut Vec<T>) {
+    transform_moves(moves, Isometry::Turn(Rotation::HalfTurn));
+}
+
+pub(super) fn reflect_moves<T: Step>(moves: &mut Vec<T>, r: Reflection) {
+    transform_moves(moves, Isometry::Reflect(r));
+}
+
+// Images already covered by a move, in whatever form it was written, aren't added again
+pub(super) fn symmetrize_moves<T: Step>(moves: &mut Vec<T>, group: Symmetry) {
+    let isometries = group.isometries();
+    let images: Vec<T> = moves
+        .iter()
+        .flat_map(|m| isometries.iter().map(move |&t| m.transform(t)))
+        .collect();
+    for image in images {
+        if !moves.iter().any(|m| m.covers(&image)) {
+            moves.push(image);
+        }
+    }
+}
+
+// Appends the image of every move so far
+fn transform_moves<T: Step>(moves: &mut Vec<T>, t: Isometry) {
+    moves.reserve(moves.len());
+    moves.append(&mut moves.iter().map(|m| m.transform(t)).collect());
+}
@@ -0,0 +1,277 @@
+use super::render::file_label;
+use super::*;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Where reading a move, move list or coordinate went wrong
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct NotationError {
+    // Character position in the text
+    pub position: usize,
+    // None when the text ends early
+    pub found: Option<char>,
+}
+
+impl Display for NotationError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self.found {
+            Some(c) => write!(f, "unexpected {:?} at position {}", c, self.position),
+            None => write!(f, "unexpected end at position {}", self.position),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+// Compact text for moves, e.g
+//
+//     U1       Up(1)
+//     UL1,2    UpLeft(1, 2), up then left like the enum
+//     0        NullMove
+//
+// Move lists are separated by whitespace, and a trailing * makes a ray of every multiple of
+// the smallest step up to the move, so R3* is R1 R2 R3 and UR2,4* is UR1,2 UR2,4.
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match *self {
+            Move::NullMove => write!(f, "0"),
+            Move::Up(i) => write!(f, "U{}", i),
+            Move::Down(i) => write!(f, "D{}", i),
+            Move::Left(i) => write!(f, "L{}", i),
+            Move::Right(i) => write!(f, "R{}", i),
+            Move::UpLeft(u, l) => write!(f, "UL{},{}", u, l),
+            Move::UpRight(u, r) => write!(f, "UR{},{}", u, r),
+            Move::DownLeft(d, l) => write!(f, "DL{},{}", d, l),
+            Move::DownRight(d, r) => write!(f, "DR{},{}", d, r),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut i = 0;
+        let m = parse_move(&chars, &mut i)?;
+        expect_end(&chars, i)?;
+        Ok(m)
+    }
+}
+
+// Every move is written out, rays aren't collapsed back into a *
+impl Display for MovePattern {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for (i, m) in self.moves().iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", m)?;
+        }
+        Ok(())
+    }
+}
+
+// Patterns are sets, so the moves come back sorted and without duplicates, e.g
+// "U1 L1 R3*" is L1 R1 R2 R3 U1. Use parse_moves to keep the order as written.
+impl FromStr for MovePattern {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_moves(s).map(MovePattern::new)
+    }
+}
+
+// A whitespace separated move list in the order it's written, with rays expanded in place
+// and any duplicates kept, e.g for replaying moves from a config file
+pub fn parse_moves(s: &str) -> Result<Vec<Move>, NotationError> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut moves = Vec::new();
+    let mut i = 0;
+
+    loop {
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        if i == chars.len() {
+            break;
+        }
+
+        let m = parse_move(&chars, &mut i)?;
+        if chars.get(i) == Some(&'*') {
+            i += 1;
+            let (x, y) = m.offset();
+            let steps = gcd(x.unsigned_abs(), y.unsigned_abs()).max(1);
+            let step = Move::from_offset(x / steps as isize, y / steps as isize);
+            (1..=steps).for_each(|mul| moves.push(step * mul));
+        } else {
+            moves.push(m);
+        }
+
+        if i < chars.len() && !chars[i].is_whitespace() {
+            return Err(error(&chars, i));
+        }
+    }
+
+    Ok(moves)
+}
+
+// A cell in algebraic notation, files a .. z then aa, ab .. and ranks from 1, e.g e4 is
+// (4, 3). Any board size works, so check the cell is on the board before using it.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coord {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Coord {
+    pub fn new(x: usize, y: usize) -> Self {
+        Coord { x, y }
+    }
+}
+
+impl From<(usize, usize)> for Coord {
+    fn from((x, y): (usize, usize)) -> Self {
+        Coord { x, y }
+    }
+}
+
+impl From<Coord> for (usize, usize) {
+    fn from(coord: Coord) -> Self {
+        (coord.x, coord.y)
+    }
+}
+
+impl Display for Coord {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", file_label(self.x), self.y + 1)
+    }
+}
+
+impl FromStr for Coord {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut i = 0;
+
+        // Files count like a spreadsheet's columns, one past a .. z then aa
+        let mut file: usize = 0;
+        while i < chars.len() && chars[i].is_ascii_lowercase() {
+            let letter = (chars[i] as u8 - b'a') as usize + 1;
+            file = file
+                .checked_mul(26)
+                .and_then(|f| f.checked_add(letter))
+                .ok_or_else(|| error(&chars, i))?;
+            i += 1;
+        }
+        if i == 0 {
+            return Err(error(&chars, 0));
+        }
+
+        let start = i;
+        let rank = parse_number(&chars, &mut i)?;
+        if rank == 0 {
+            return Err(error(&chars, start));
+        }
+        expect_end(&chars, i)?;
+
+        Ok(Coord::new(file - 1, rank - 1))
+    }
+}
+
+fn parse_move(chars: &[char], i: &mut usize) -> Result<Move, NotationError> {
+    if chars.get(*i) == Some(&'0') {
+        *i += 1;
+        return Ok(Move::NullMove);
+    }
+
+    let vertical = match chars.get(*i) {
+        Some('U') => Some(true),
+        Some('D') => Some(false),
+        _ => None,
+    };
+    if vertical.is_some() {
+        *i += 1;
+    }
+    let horizontal = match chars.get(*i) {
+        Some('L') => Some(false),
+        Some('R') => Some(true),
+        _ => None,
+    };
+    if horizontal.is_some() {
+        *i += 1;
+    }
+    if vertical.is_none() && horizontal.is_none() {
+        return Err(error(chars, *i));
+    }
+
+    let first = parse_number(chars, i)?;
+    let up = |n| {
+        if vertical == Some(true) {
+            Move::Up(n)
+        } else {
+            Move::Down(n)
+        }
+    };
+    let right = |n| {
+        if horizontal == Some(true) {
+            Move::Right(n)
+        } else {
+            Move::Left(n)
+        }
+    };
+    match (vertical, horizontal) {
+        (Some(_), None) => Ok(up(first)),
+        (None, _) => Ok(right(first)),
+        (Some(_), Some(_)) => {
+            if chars.get(*i) != Some(&',') {
+                return Err(error(chars, *i));
+            }
+            *i += 1;
+            let second = parse_number(chars, i)?;
+            // UL0,2 and the like come out as straight moves, which shift can handle
+            let (x, y) = up(first).combine(right(second)).offset();
+            Ok(Move::from_offset(x, y))
+        }
+    }
+}
+
+fn parse_number(chars: &[char], i: &mut usize) -> Result<usize, NotationError> {
+    let start = *i;
+    let mut n: usize = 0;
+    while let Some(digit) = chars.get(*i).and_then(|c| c.to_digit(10)) {
+        n = n
+            .checked_mul(10)
+            .and_then(|n| n.checked_add(digit as usize))
+            .ok_or_else(|| error(chars, *i))?;
+        *i += 1;
+    }
+    if *i == start {
+        return Err(error(chars, start));
+    }
+    Ok(n)
+}
+
+fn expect_end(chars: &[char], i: usize) -> Result<(), NotationError> {
+    match chars.get(i) {
+        Some(_) => Err(error(chars, i)),
+        None => Ok(()),
+    }
+}
+
+fn error(chars: &[char], position: usize) -> NotationError {
+    NotationError {
+        position,
+        found: chars.get(position).copied(),
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
@@ -1,5 +1,7 @@
 use super::*;
-use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Shl, ShlAssign};
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, ShlAssign,
+};
 
 impl<N: Unsigned, R: PrimUInt> Shl<Move> for &mut BitBoard<N, R> {
     type Output = Self;
@@ -44,35 +46,22 @@ impl<N: Unsigned, R: PrimUInt> BitAnd for &BitBoard<N, R> {
 
     fn bitand(self, rhs: Self) -> Self::Output {
         let mut result = self.clone();
-        unsafe {
-            result
-                .block_iter_mut()
-                .zip(rhs.block_iter())
-                .for_each(|(lblock, rblock)| *lblock &= rblock);
-            result
-        }
+        result.and_blocks(rhs);
+        result
     }
 }
 
 impl<N: Unsigned, R: PrimUInt> BitAnd for BitBoard<N, R> {
     type Output = Self;
     fn bitand(mut self, rhs: Self) -> Self::Output {
-        unsafe {
-            self.block_iter_mut()
-                .zip(rhs.block_iter())
-                .for_each(|(lblock, rblock)| *lblock &= rblock);
-            self
-        }
+        self.and_blocks(&rhs);
+        self
     }
 }
 
 impl<N: Unsigned, R: PrimUInt> BitAndAssign<&Self> for BitBoard<N, R> {
     fn bitand_assign(&mut self, rhs: &Self) {
-        unsafe {
-            self.block_iter_mut()
-                .zip(rhs.block_iter())
-                .for_each(|(lblock, rblock)| *lblock &= rblock);
-        }
+        self.and_blocks(rhs);
     }
 }
 
@@ -80,12 +69,8 @@ impl<N: Unsigned, R: PrimUInt> BitOr for &mut BitBoard<N, R> {
     type Output = Self;
 
     fn bitor(self, rhs: Self) -> Self::Output {
-        unsafe {
-            self.block_iter_mut()
-                .zip(rhs.block_iter())
-                .for_each(|(lblock, rblock)| *lblock |= rblock);
-            self
-        }
+        self.or_blocks(rhs);
+        self
     }
 }
 
@@ -94,13 +79,8 @@ impl<N: Unsigned, R: PrimUInt> BitOr for &BitBoard<N, R> {
 
     fn bitor(self, rhs: Self) -> Self::Output {
         let mut result = self.clone();
-        unsafe {
-            result
-                .block_iter_mut()
-                .zip(rhs.block_iter())
-                .for_each(|(lblock, rblock)| *lblock |= rblock);
-            result
-        }
+        result.or_blocks(rhs);
+        result
     }
 }
 
@@ -108,32 +88,20 @@ impl<N: Unsigned, R: PrimUInt> BitOr for BitBoard<N, R> {
     type Output = Self;
 
     fn bitor(mut self, rhs: Self) -> Self::Output {
-        unsafe {
-            self.block_iter_mut()
-                .zip(rhs.block_iter())
-                .for_each(|(lblock, rblock)| *lblock |= rblock);
-            self
-        }
+        self.or_blocks(&rhs);
+        self
     }
 }
 
 impl<N: Unsigned, R: PrimUInt> BitOrAssign<&Self> for BitBoard<N, R> {
     fn bitor_assign(&mut self, rhs: &Self) {
-        unsafe {
-            self.block_iter_mut()
-                .zip(rhs.block_iter())
-                .for_each(|(lblock, rblock)| *lblock |= rblock);
-        }
+        self.or_blocks(rhs);
     }
 }
 
 impl<N: Unsigned, R: PrimUInt> BitOrAssign for BitBoard<N, R> {
     fn bitor_assign(&mut self, rhs: Self) {
-        unsafe {
-            self.block_iter_mut()
-                .zip(rhs.block_iter())
-                .for_each(|(lblock, rblock)| *lblock |= rblock);
-        }
+        self.or_blocks(&rhs);
     }
 }
 
@@ -142,22 +110,97 @@ impl<N: Unsigned, R: PrimUInt> BitXor for &BitBoard<N, R> {
 
     fn bitxor(self, rhs: Self) -> Self::Output {
         let mut result = self.clone();
-        unsafe {
-            result
-                .block_iter_mut()
-                .zip(rhs.block_iter())
-                .for_each(|(lblock, rblock)| *lblock ^= rblock);
-            result
-        }
+        result.xor_blocks(rhs);
+        result
     }
 }
 
 impl<N: Unsigned, R: PrimUInt> BitXorAssign<&Self> for BitBoard<N, R> {
     fn bitxor_assign(&mut self, rhs: &Self) {
-        unsafe {
-            self.block_iter_mut()
-                .zip(rhs.block_iter())
-                .for_each(|(lblock, rblock)| *lblock ^= rblock);
+        self.xor_blocks(rhs);
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Not for &BitBoard<N, R> {
+    type Output = BitBoard<N, R>;
+
+    fn not(self) -> Self::Output {
+        !self.clone()
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Not for BitBoard<N, R> {
+    type Output = Self;
+
+    fn not(mut self) -> Self::Output {
+        self.blocks_mut()
+            .iter_mut()
+            .for_each(|block| *block = !*block);
+        if Self::HAS_BLOCK_MASK {
+            if let Some(block) = self.blocks_mut().last_mut() {
+                *block &= Self::last_block_mask();
+            }
         }
+        self
+    }
+}
+
+// Block-wise kernels shared by all of the operator impls above
+impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
+    fn and_blocks(&mut self, rhs: &Self) {
+        zip_blocks(self.blocks_mut(), rhs.blocks(), Self::PARALLEL, and_kernel);
+    }
+
+    fn or_blocks(&mut self, rhs: &Self) {
+        zip_blocks(self.blocks_mut(), rhs.blocks(), Self::PARALLEL, or_kernel);
+    }
+
+    fn xor_blocks(&mut self, rhs: &Self) {
+        zip_blocks(self.blocks_mut(), rhs.blocks(), Self::PARALLEL, xor_kernel);
+    }
+}
+
+pub(super) fn zip_blocks<R: PrimUInt>(
+    dst: &mut [R],
+    src: &[R],
+    parallel: bool,
+    kernel: fn(&mut [R], &[R]),
+) {
+    if parallel {
+        parallel::for_each_chunk(dst, |start, chunk| {
+            kernel(chunk, &src[start..start + chunk.len()])
+        });
+    } else {
+        kernel(dst, src);
     }
 }
+
+#[cfg(not(feature = "simd"))]
+pub(super) fn and_kernel<R: PrimUInt>(dst: &mut [R], src: &[R]) {
+    dst.iter_mut().zip(src).for_each(|(l, r)| *l &= *r);
+}
+
+#[cfg(feature = "simd")]
+pub(super) fn and_kernel<R: PrimUInt>(dst: &mut [R], src: &[R]) {
+    simd::and_assign(simd::bytes_mut(dst), simd::bytes(src));
+}
+
+#[cfg(not(feature = "simd"))]
+pub(super) fn or_kernel<R: PrimUInt>(dst: &mut [R], src: &[R]) {
+    dst.iter_mut().zip(src).for_each(|(l, r)| *l |= *r);
+}
+
+#[cfg(feature = "simd")]
+pub(super) fn or_kernel<R: PrimUInt>(dst: &mut [R], src: &[R]) {
+    simd::or_assign(simd::bytes_mut(dst), simd::bytes(src));
+}
+
+#[cfg(not(feature = "simd"))]
+pub(super) fn xor_kernel<R: PrimUInt>(dst: &mut [R], src: &[R]) {
+    dst.iter_mut().zip(src).for_each(|(l, r)| *l ^= *r);
+}
+
+#[cfg(feature = "simd")]
+pub(super) fn xor_kernel<R: PrimUInt>(dst: &mut [R], src: &[R]) {
+    simd::xor_assign(simd::bytes_mut(dst), simd::bytes(src));
+}
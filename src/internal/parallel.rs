@@ -0,0 +1,80 @@
+// Helpers for splitting block ranges across scoped threads.
+//
+// Only boards at or above the threshold in statics.rs go through here, and only when the
+// `parallel` feature is on. Worker threads flag themselves so that anything they call which
+// would also split ( e.g. a shift inside a parallel Moves::collect ) runs inline instead of
+// spawning threads of its own.
+
+use std::cell::Cell;
+use std::cmp;
+use std::thread;
+
+// Chunks smaller than this aren't worth a thread
+const MIN_CHUNK_BYTES: usize = 8 * 1024;
+
+thread_local! {
+    static IN_WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+// How many threads to use for `len` items, each costing about as much as `item_bytes` bytes
+pub(crate) fn workers(len: usize, item_bytes: usize) -> usize {
+    if IN_WORKER.with(|w| w.get()) {
+        return 1;
+    }
+
+    let available = thread::available_parallelism().map_or(1, |n| n.get());
+    let useful = cmp::max(1, len.saturating_mul(item_bytes) / MIN_CHUNK_BYTES);
+    cmp::min(cmp::min(available, useful), cmp::max(len, 1))
+}
+
+// Runs `op` on roughly equal chunks of `dst`, passing the index of the first item in each
+pub(crate) fn for_each_chunk<T: Send>(dst: &mut [T], op: impl Fn(usize, &mut [T]) + Sync) {
+    let threads = workers(dst.len(), std::mem::size_of::<T>());
+    if threads <= 1 {
+        return op(0, dst);
+    }
+
+    let chunk_len = dst.len().div_ceil(threads);
+    let op = &op;
+    thread::scope(|s| {
+        for (i, chunk) in dst.chunks_mut(chunk_len).enumerate() {
+            s.spawn(move || {
+                IN_WORKER.with(|w| w.set(true));
+                op(i * chunk_len, chunk)
+            });
+        }
+    });
+}
+
+// Splits `src` into roughly equal chunks, maps each on its own thread and folds the results
+pub(crate) fn map_reduce<T: Sync, U: Send>(
+    src: &[T],
+    item_bytes: usize,
+    map: impl Fn(&[T]) -> U + Sync,
+    reduce: impl Fn(U, U) -> U,
+) -> U {
+    let threads = workers(src.len(), item_bytes);
+    if threads <= 1 {
+        return map(src);
+    }
+
+    let chunk_len = src.len().div_ceil(threads);
+    let map = &map;
+    thread::scope(|s| {
+        let handles: Vec<_> = src
+            .chunks(chunk_len)
+            .map(|chunk| {
+                s.spawn(move || {
+                    IN_WORKER.with(|w| w.set(true));
+                    map(chunk)
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .reduce(reduce)
+            .unwrap()
+    })
+}
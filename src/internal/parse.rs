@@ -0,0 +1,188 @@
+use super::*;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Where and why a board failed to parse. Lines and columns count from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedChar(char),
+    WrongWidth { expected: usize, found: usize },
+    WrongHeight { expected: usize, found: usize },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            ParseErrorKind::WrongWidth { expected, found } => {
+                write!(f, "expected {} cells in the row, found {}", expected, found)
+            }
+            ParseErrorKind::WrongHeight { expected, found } => {
+                write!(f, "expected {} rows, found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Parses the Display format, or grids drawn with . for empty and x or # for set cells.
+// Whitespace between cells is optional, and rows may start or end with a rank number.
+// A row of file letters ( a b c ... ) above or below the grid is skipped.
+impl<N: Unsigned, R: PrimUInt> FromStr for BitBoard<N, R> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut board = Self::default();
+        parse_grid(s, N::USIZE, N::USIZE, |x, y| board.set(x, y))?;
+        Ok(board)
+    }
+}
+
+// Calls `set` for every set cell of a width x height grid, rows given top-down
+pub(super) fn parse_grid(
+    s: &str,
+    width: usize,
+    height: usize,
+    mut set: impl FnMut(usize, usize),
+) -> Result<(), ParseError> {
+    let mut rows = 0;
+    let mut last_line = 0;
+    let mut first_extra = None;
+
+    for (idx, line) in s.lines().enumerate() {
+        let line_no = idx + 1;
+        last_line = line_no;
+        if line.trim().is_empty() || is_file_labels(line, width) {
+            continue;
+        }
+
+        let rank = height.checked_sub(rows).filter(|&r| r > 0);
+        let cells = row_cells(line, width, rank, line_no)?;
+        if rows < height {
+            let y = height - rows - 1;
+            cells
+                .iter()
+                .enumerate()
+                .filter(|(_, &cell)| cell)
+                .for_each(|(x, _)| set(x, y));
+        } else if first_extra.is_none() {
+            first_extra = Some(line_no);
+        }
+        rows += 1;
+    }
+
+    // Too many rows points at the first one that doesn't fit, too few at the end of the input
+    if rows != height {
+        return Err(ParseError {
+            line: first_extra.unwrap_or(last_line + 1),
+            column: 1,
+            kind: ParseErrorKind::WrongHeight {
+                expected: height,
+                found: rows,
+            },
+        });
+    }
+    Ok(())
+}
+
+// The cells of one row, dropping rank numbers when the row has too many cells with them.
+// `rank` is the row's own rank, None for rows past the bottom of the board.
+fn row_cells(
+    line: &str,
+    width: usize,
+    rank: Option<usize>,
+    line_no: usize,
+) -> Result<Vec<bool>, ParseError> {
+    let unexpected = |column: usize, c: char| ParseError {
+        line: line_no,
+        column: column + 1,
+        kind: ParseErrorKind::UnexpectedChar(c),
+    };
+
+    // Any digit could be part of a label, so they're all kept until the labels are known
+    let mut cells = Vec::with_capacity(width);
+    for (column, c) in line.chars().enumerate() {
+        match c {
+            '0'..='9' | '.' | 'x' | 'X' | '#' => cells.push((column, c)),
+            c if c.is_whitespace() || c == '|' => (),
+            c => return Err(unexpected(column, c)),
+        }
+    }
+
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    // On a 0/1 grid any cell looks like a label, so only the row's rank counts as one
+    let label_len = |token: Option<&&str>| match token {
+        Some(t) if t.chars().all(|c| c.is_ascii_digit()) => match rank {
+            Some(rank) if t.parse() != Ok(rank) => 0,
+            _ => t.len(),
+        },
+        _ => 0,
+    };
+
+    let lead = label_len(tokens.first());
+    let trail = if tokens.len() > 1 {
+        label_len(tokens.last())
+    } else {
+        0
+    };
+
+    // Either label can be missing, so take the first way of dropping them that leaves exactly
+    // a row of valid cells. When none does the leading label is dropped first, so the error
+    // points at the extra cells.
+    let n = cells.len();
+    let valid = |&(start, end): &(usize, usize)| {
+        end >= start
+            && end - start == width
+            && cells[start..end]
+                .iter()
+                .all(|&(_, c)| matches!(c, '0' | '1' | '.' | 'x' | 'X' | '#'))
+    };
+    let (start, end) = [(0, n), (lead, n), (0, n - trail), (lead, n - trail)]
+        .iter()
+        .copied()
+        .find(valid)
+        .unwrap_or_else(|| {
+            let start = if n > width { lead } else { 0 };
+            let end = if n - start > width { n - trail } else { n };
+            (start, end)
+        });
+
+    let mut row = Vec::with_capacity(width);
+    for &(column, c) in &cells[start..end] {
+        match c {
+            '1' | 'x' | 'X' | '#' => row.push(true),
+            '0' | '.' => row.push(false),
+            c => return Err(unexpected(column, c)),
+        }
+    }
+
+    if row.len() != width {
+        return Err(ParseError {
+            line: line_no,
+            column: line.chars().count() + 1,
+            kind: ParseErrorKind::WrongWidth {
+                expected: width,
+                found: row.len(),
+            },
+        });
+    }
+    Ok(row)
+}
+
+fn is_file_labels(line: &str, width: usize) -> bool {
+    let letters: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    letters.len() == width
+        && letters
+            .iter()
+            .zip(b'a'..)
+            .all(|(&c, l)| c.to_ascii_lowercase() == l as char)
+}
@@ -0,0 +1,129 @@
+use super::*;
+use std::iter::FromIterator;
+use std::ops::BitOr;
+
+// A set of moves that isn't tied to a board, so it can be built once and applied to boards
+// of any size, e.g
+//
+//     static KNIGHT: LazyLock<MovePattern> = LazyLock::new(|| {
+//         MovePattern::new(vec![Move::UpRight(2, 1), Move::UpRight(1, 2)])
+//             .rotate(Rotation::Clockwise)
+//             .mirror()
+//     });
+//
+// Moves are normalised so each distance is only stored once, whichever way it was written,
+// and kept sorted so equal patterns compare equal.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "Vec<Move>", into = "Vec<Move>")
+)]
+pub struct MovePattern {
+    moves: Vec<Move>,
+}
+
+impl MovePattern {
+    pub fn new(moves: impl IntoIterator<Item = Move>) -> Self {
+        let mut moves: Vec<Move> = moves
+            .into_iter()
+            .map(|m| {
+                let (x, y) = m.offset();
+                Move::from_offset(x, y)
+            })
+            .collect();
+        moves.sort();
+        moves.dedup();
+        MovePattern { moves }
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        MovePattern::new(self.moves.iter().chain(other.moves.iter()).copied())
+    }
+
+    pub fn repeat(self, i: usize) -> Self {
+        self.transform(|moves| repeat_moves(moves, i))
+    }
+
+    pub fn rotate(self, r: Rotation) -> Self {
+        self.transform(|moves| rotate_moves(moves, r))
+    }
+
+    pub fn mirror(self) -> Self {
+        self.transform(mirror_moves)
+    }
+
+    pub fn reflect(self, r: Reflection) -> Self {
+        self.transform(|moves| reflect_moves(moves, r))
+    }
+
+    pub fn symmetrize(self, group: Symmetry) -> Self {
+        self.transform(|moves| symmetrize_moves(moves, group))
+    }
+
+    // Every cell reached by a move of the pattern from a set cell of the board
+    pub fn apply<N: Unsigned, R: PrimUInt>(&self, board: &BitBoard<N, R>) -> BitBoard<N, R> {
+        board.moves().pattern(self).collect()
+    }
+
+    fn transform(self, op: impl FnOnce(&mut Vec<Move>)) -> Self {
+        let mut moves = self.moves;
+        op(&mut moves);
+        MovePattern::new(moves)
+    }
+}
+
+impl From<Vec<Move>> for MovePattern {
+    fn from(moves: Vec<Move>) -> Self {
+        MovePattern::new(moves)
+    }
+}
+
+impl From<MovePattern> for Vec<Move> {
+    fn from(pattern: MovePattern) -> Self {
+        pattern.moves
+    }
+}
+
+impl FromIterator<Move> for MovePattern {
+    fn from_iter<I: IntoIterator<Item = Move>>(iter: I) -> Self {
+        MovePattern::new(iter)
+    }
+}
+
+impl BitOr for &MovePattern {
+    type Output = MovePattern;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> Moves<'a, N, R> {
+    // Adds every move of a pattern to the builder
+    pub fn pattern(self, pattern: &MovePattern) -> Self {
+        pattern.moves().iter().fold(self, |m, &p| m.translate(p))
+    }
+}
+
+impl Betza {
+    // The moves of every ray as a pattern. Riders without a range are cut off at max_range
+    pub fn to_pattern(&self, max_range: usize) -> MovePattern {
+        self.rays()
+            .iter()
+            .flat_map(|ray| (1..=ray.range.unwrap_or(max_range)).map(move |i| ray.step * i))
+            .collect()
+    }
+}
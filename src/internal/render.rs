@@ -0,0 +1,261 @@
+use super::*;
+use std::fmt::{Display, Formatter};
+
+// How the files ( columns ) and ranks ( rows ) are labelled
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Labels {
+    None,
+    // Files a, b, c .. and ranks 1, 2, 3 ..
+    Algebraic,
+    // Files and ranks 0, 1, 2 ..
+    Numeric,
+}
+
+// Which corner ( 0, 0 ) is drawn in
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Origin {
+    BottomLeft,
+    TopLeft,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Colour {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Colour {
+    fn ansi_code(self) -> u8 {
+        30 + self as u8
+    }
+
+    pub(super) fn css_name(self) -> &'static str {
+        match self {
+            Colour::Black => "black",
+            Colour::Red => "red",
+            Colour::Green => "green",
+            Colour::Yellow => "yellow",
+            Colour::Blue => "blue",
+            Colour::Magenta => "magenta",
+            Colour::Cyan => "cyan",
+            Colour::White => "white",
+        }
+    }
+}
+
+// What a set cell of an overlay is drawn as
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct Glyph {
+    pub symbol: char,
+    pub colour: Option<Colour>,
+}
+
+impl Glyph {
+    pub fn new(symbol: char) -> Self {
+        Glyph {
+            symbol,
+            colour: None,
+        }
+    }
+
+    pub fn colour(mut self, colour: Colour) -> Self {
+        self.colour = Some(colour);
+        self
+    }
+}
+
+impl From<char> for Glyph {
+    fn from(symbol: char) -> Self {
+        Glyph::new(symbol)
+    }
+}
+
+struct Overlay<'a, N: Unsigned, R: PrimUInt> {
+    pub name: &'a str,
+    pub board: &'a BitBoard<N, R>,
+    pub glyph: Glyph,
+}
+
+// Draws one or more boards into a single grid. Overlays added later are drawn on top of
+// earlier ones, and the named ones are listed under the grid with their glyphs.
+//
+//     let text = Renderer::new()
+//         .labels(Labels::Algebraic)
+//         .overlay("white", &white, 'W')
+//         .overlay("black", &black, 'B')
+//         .overlay("attacked", &attacked, Glyph::new('*').colour(Colour::Red))
+//         .ansi(true)
+//         .to_string();
+pub struct Renderer<'a, N: Unsigned, R: PrimUInt> {
+    overlays: Vec<Overlay<'a, N, R>>,
+    pub(super) empty: Glyph,
+    labels: Labels,
+    pub(super) origin: Origin,
+    ansi: bool,
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> Default for Renderer<'a, N, R> {
+    fn default() -> Self {
+        Renderer {
+            overlays: Vec::new(),
+            empty: Glyph::new('.'),
+            labels: Labels::None,
+            origin: Origin::BottomLeft,
+            ansi: false,
+        }
+    }
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> Renderer<'a, N, R> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn overlay(
+        mut self,
+        name: &'a str,
+        board: &'a BitBoard<N, R>,
+        glyph: impl Into<Glyph>,
+    ) -> Self {
+        self.overlays.push(Overlay {
+            name,
+            board,
+            glyph: glyph.into(),
+        });
+        self
+    }
+
+    pub fn empty(mut self, glyph: impl Into<Glyph>) -> Self {
+        self.empty = glyph.into();
+        self
+    }
+
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn origin(mut self, origin: Origin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    // Colours are only written when this is on, so the output stays plain for files and
+    // terminals that don't support them
+    pub fn ansi(mut self, ansi: bool) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    fn glyph_at(&self, x: usize, y: usize) -> Glyph {
+        self.overlay_at(x, y).unwrap_or(self.empty)
+    }
+
+    // The glyph of the last overlay added with (x, y) set, shared with to_svg
+    pub(super) fn overlay_at(&self, x: usize, y: usize) -> Option<Glyph> {
+        self.overlays
+            .iter()
+            .rev()
+            .find(|o| o.board.is_set(x, y))
+            .map(|o| o.glyph)
+    }
+
+    fn write_glyph(&self, f: &mut Formatter, glyph: Glyph, width: usize) -> std::fmt::Result {
+        match glyph.colour {
+            Some(colour) if self.ansi => write!(
+                f,
+                "\x1b[{}m{:<width$}\x1b[0m",
+                colour.ansi_code(),
+                glyph.symbol,
+                width = width
+            ),
+            _ => write!(f, "{:<width$}", glyph.symbol, width = width),
+        }
+    }
+
+    fn file_label(&self, x: usize) -> String {
+        match self.labels {
+            Labels::Algebraic => file_label(x),
+            _ => x.to_string(),
+        }
+    }
+
+    fn rank_label(&self, y: usize) -> String {
+        match self.labels {
+            Labels::Algebraic => (y + 1).to_string(),
+            _ => y.to_string(),
+        }
+    }
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> Display for Renderer<'a, N, R> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let s = N::USIZE;
+        let labelled = self.labels != Labels::None;
+        let cell = match labelled {
+            true => (0..s).map(|x| self.file_label(x).len()).max().unwrap_or(1),
+            false => 1,
+        };
+        let margin = match labelled {
+            true => (0..s).map(|y| self.rank_label(y).len()).max().unwrap_or(1),
+            false => 0,
+        };
+
+        for c in 0..s {
+            let y = match self.origin {
+                Origin::BottomLeft => s - c - 1,
+                Origin::TopLeft => c,
+            };
+
+            if labelled {
+                write!(f, "{:>width$} ", self.rank_label(y), width = margin)?;
+            }
+            for x in 0..s {
+                self.write_glyph(f, self.glyph_at(x, y), cell)?;
+                write!(f, " ")?;
+            }
+            writeln!(f)?;
+        }
+
+        if labelled {
+            write!(f, "{:width$} ", "", width = margin)?;
+            for x in 0..s {
+                write!(f, "{:<width$} ", self.file_label(x), width = cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        for o in self.overlays.iter().filter(|o| !o.name.is_empty()) {
+            self.write_glyph(f, o.glyph, 1)?;
+            writeln!(f, " {}", o.name)?;
+        }
+        write!(f, "")
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
+    // A renderer drawing this board with x for set cells
+    pub fn renderer(&self) -> Renderer<'_, N, R> {
+        Renderer::new().overlay("", self, 'x')
+    }
+}
+
+// Letters for a file, running a .. z then aa, ab .. for boards wider than 26
+pub(super) fn file_label(mut x: usize) -> String {
+    let mut label = Vec::new();
+    loop {
+        label.push(b'a' + (x % 26) as u8);
+        if x < 26 {
+            break;
+        }
+        x = x / 26 - 1;
+    }
+    label.reverse();
+    String::from_utf8(label).unwrap()
+}
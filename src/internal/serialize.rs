@@ -0,0 +1,119 @@
+use super::*;
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{self, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt::Formatter;
+
+// Text formats get the size and ASCII rows, top-down like Display. A list of set cells
+// can be given instead of the rows when deserializing.
+//
+//     { "size": 3, "rows": [ "..x", ".x.", "x.." ] }
+//     { "size": 3, "cells": [ [0, 0], [1, 1], [2, 2] ] }
+//
+// Binary formats get the bytes written by write_to, header included.
+#[derive(Serialize, Deserialize)]
+struct Readable {
+    size: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rows: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cells: Option<Vec<(usize, usize)>>,
+}
+
+impl<N: Unsigned, R: PrimUInt> Serialize for BitBoard<N, R> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        if !s.is_human_readable() {
+            return serialize_packed(self, s);
+        }
+
+        let rows = (0..N::USIZE)
+            .rev()
+            .map(|y| {
+                (0..N::USIZE)
+                    .map(|x| if self.is_set(x, y) { 'x' } else { '.' })
+                    .collect()
+            })
+            .collect();
+
+        Readable {
+            size: N::USIZE,
+            rows: Some(rows),
+            cells: None,
+        }
+        .serialize(s)
+    }
+}
+
+impl<'de, N: Unsigned, R: PrimUInt> Deserialize<'de> for BitBoard<N, R> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        if !d.is_human_readable() {
+            return deserialize_packed(d);
+        }
+
+        let readable = Readable::deserialize(d)?;
+        if readable.size != N::USIZE {
+            return Err(de::Error::custom(SizeMismatch {
+                expected: N::USIZE,
+                found: readable.size,
+            }));
+        }
+
+        match (readable.rows, readable.cells) {
+            (Some(rows), None) => rows.join("\n").parse().map_err(de::Error::custom),
+            (None, Some(cells)) => {
+                let off_board = cells.iter().find(|&&(x, y)| x >= N::USIZE || y >= N::USIZE);
+                if let Some((x, y)) = off_board {
+                    return Err(de::Error::custom(format!(
+                        "cell ({}, {}) is off the board",
+                        x, y
+                    )));
+                }
+                Ok(BitBoard::new(cells))
+            }
+            _ => Err(de::Error::custom("expected one of rows or cells")),
+        }
+    }
+}
+
+pub(crate) fn serialize_packed<N: Unsigned, R: PrimUInt, S: Serializer>(
+    board: &BitBoard<N, R>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    let mut bytes = Vec::new();
+    board.write_to(&mut bytes).map_err(ser::Error::custom)?;
+    s.serialize_bytes(&bytes)
+}
+
+pub(crate) fn deserialize_packed<'de, N: Unsigned, R: PrimUInt, D: Deserializer<'de>>(
+    d: D,
+) -> Result<BitBoard<N, R>, D::Error> {
+    let bytes = d.deserialize_bytes(BytesVisitor)?;
+    BitBoard::read_from(&bytes[..]).map_err(de::Error::custom)
+}
+
+// Formats without a native bytes type hand them over as a sequence
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "a serialized board")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
@@ -28,118 +28,222 @@ enum EdgeMask {
     Right(usize),
 }
 
+// The shape of a board as far as shifting is concerned. Typed boards build this from their
+// associated constants, and runtime sized boards from their dimension.
+#[derive(Copy, Clone)]
+pub(super) struct Shape {
+    pub width: usize,
+    pub height: usize,
+    pub parallel: bool,
+}
+
 impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
     pub(super) fn shift(&mut self, m: Move) {
-        unsafe {
-            match m {
-                Move::Up(i) if i < N::USIZE => self.shift_internal(i * N::USIZE, Shift::Left, None),
-                Move::Down(i) if i < N::USIZE => {
-                    self.shift_internal(i * N::USIZE, Shift::Right, None)
-                }
-                Move::Left(i) if i < N::USIZE => {
-                    self.shift_internal(i, Shift::Right, Some(EdgeMask::Right(i)))
-                }
-                Move::Right(i) if i < N::USIZE => {
-                    self.shift_internal(i, Shift::Left, Some(EdgeMask::Left(i)))
-                }
-                Move::UpLeft(u, l) if u < N::USIZE && l < N::USIZE => {
-                    self.shift_internal(u * N::USIZE - l, Shift::Left, Some(EdgeMask::Right(l)))
-                }
-                Move::UpRight(u, r) if u < N::USIZE && r < N::USIZE => {
-                    self.shift_internal(u * N::USIZE + r, Shift::Left, Some(EdgeMask::Left(r)))
-                }
-                Move::DownLeft(d, l) if d < N::USIZE && l < N::USIZE => {
-                    self.shift_internal(d * N::USIZE + l, Shift::Right, Some(EdgeMask::Right(l)))
-                }
-                Move::DownRight(d, r) if d < N::USIZE && r < N::USIZE => {
-                    self.shift_internal(d * N::USIZE - r, Shift::Right, Some(EdgeMask::Left(r)))
-                }
-                Move::NullMove => (),
-                _ => self.reset(),
-            }
+        shift_move(self.blocks_mut(), Self::SHAPE, m);
+    }
+}
+
+#[inline(always)]
+fn block_bits<R: PrimUInt>() -> usize {
+    std::mem::size_of::<R>() * 8
+}
+
+pub(super) fn shift_move<R: PrimUInt>(blocks: &mut [R], shape: Shape, m: Move) {
+    let (w, h) = (shape.width, shape.height);
+    let mut shift = |rhs, direction, mask| shift_internal(blocks, shape, rhs, direction, mask);
+
+    match m {
+        Move::Up(i) if i < h => shift(i * w, Shift::Left, None),
+        Move::Down(i) if i < h => shift(i * w, Shift::Right, None),
+        Move::Left(i) if i < w => shift(i, Shift::Right, Some(EdgeMask::Right(i))),
+        Move::Right(i) if i < w => shift(i, Shift::Left, Some(EdgeMask::Left(i))),
+        Move::UpLeft(u, l) if u < h && l < w => {
+            shift(u * w - l, Shift::Left, Some(EdgeMask::Right(l)))
+        }
+        Move::UpRight(u, r) if u < h && r < w => {
+            shift(u * w + r, Shift::Left, Some(EdgeMask::Left(r)))
+        }
+        Move::DownLeft(d, l) if d < h && l < w => {
+            shift(d * w + l, Shift::Right, Some(EdgeMask::Right(l)))
+        }
+        Move::DownRight(d, r) if d < h && r < w => {
+            shift(d * w - r, Shift::Right, Some(EdgeMask::Left(r)))
+        }
+        Move::NullMove => (),
+        _ => blocks.iter_mut().for_each(|block| *block = R::zero()),
+    }
+}
+
+fn shift_internal<R: PrimUInt>(
+    blocks: &mut [R],
+    shape: Shape,
+    rhs: usize,
+    direction: Shift,
+    mask: Option<EdgeMask>,
+) {
+    if shape.parallel {
+        shift_parallel(blocks, shape.width, rhs, direction, mask);
+    } else {
+        let edge_masks: Option<Vec<R>> = mask.map(|m| {
+            (0..blocks.len())
+                .map(|i| edge_mask(m, i, shape.width))
+                .collect()
+        });
+
+        shift_blocks(blocks, rhs, direction, edge_masks.as_deref());
+    }
+
+    if let Some(block) = blocks.last_mut() {
+        *block &= last_block_mask(shape.width * shape.height);
+    }
+}
+
+#[cfg(not(all(feature = "simd", target_endian = "little")))]
+fn shift_blocks<R: PrimUInt>(
+    blocks: &mut [R],
+    mut rhs: usize,
+    direction: Shift,
+    edge_masks: Option<&[R]>,
+) {
+    while rhs > 0 {
+        let mut prev_lost = R::zero();
+
+        enumerate_blocks(blocks, direction, |idx, block| {
+            let mask = edge_masks.map_or(R::max_value(), |masks| masks[idx]);
+            prev_lost = shift_block(direction, rhs, prev_lost, block, mask);
+        });
+
+        rhs -= std::cmp::min(block_bits::<R>(), rhs);
+    }
+}
+
+// On little endian targets the blocks are one long number in memory, so the board can be
+// shifted as bytes whatever R is. Whole bytes are moved in one go, the rest of the shift
+// and its carry are done by the vector kernels, and the edge mask is applied to the whole
+// board afterwards. Masking afterwards is equivalent to masking during the shift because
+// the lost bits are always taken from the unmasked block.
+#[cfg(all(feature = "simd", target_endian = "little"))]
+fn shift_blocks<R: PrimUInt>(
+    blocks: &mut [R],
+    rhs: usize,
+    direction: Shift,
+    edge_masks: Option<&[R]>,
+) {
+    let bytes = simd::bytes_mut(blocks);
+    let len = bytes.len();
+    let whole = std::cmp::min(rhs / 8, len);
+    match direction {
+        Shift::Left => {
+            bytes.copy_within(0..len - whole, whole);
+            bytes[..whole].iter_mut().for_each(|b| *b = 0);
+        }
+        Shift::Right => {
+            bytes.copy_within(whole..len, 0);
+            bytes[len - whole..].iter_mut().for_each(|b| *b = 0);
         }
     }
 
-    unsafe fn shift_internal(&mut self, mut rhs: usize, direction: Shift, mask: Option<EdgeMask>) {
-        let edge_masks: Vec<R> = (0..Self::REQUIRED_BLOCKS)
-            .into_iter()
-            .map(|i| {
-                if let Some(m) = mask {
-                    Self::edge_mask(m, i)
-                } else {
-                    R::max_value()
-                }
-            })
-            .collect();
-
-        while rhs > 0 {
-            let mut prev_lost = R::zero();
-
-            self.enumerate_blocks(direction, |idx, block| {
-                prev_lost = Self::shift_block(direction, rhs, prev_lost, block, edge_masks[idx]);
-            });
-
-            rhs -= std::cmp::min(Self::BLOCK_SIZE_BITS, rhs);
+    let by = (rhs % 8) as u32;
+    if by > 0 {
+        match direction {
+            Shift::Left => simd::shl_bits(bytes, by),
+            Shift::Right => simd::shr_bits(bytes, by),
         }
+    }
+
+    if let Some(masks) = edge_masks {
+        simd::and_assign(bytes, simd::bytes(masks));
+    }
+}
 
-        if Self::HAS_BLOCK_MASK {
-            if let Some(block) = self.block_iter_mut().last() {
-                *block &= Self::last_block_mask();
+// Every block of the result depends on at most two blocks of the source, so working from
+// a copy of the board lets each thread fill its own chunk without carrying lost bits
+// between chunks. The edge masks are also only calculated for the blocks in each chunk.
+fn shift_parallel<R: PrimUInt>(
+    blocks: &mut [R],
+    width: usize,
+    rhs: usize,
+    direction: Shift,
+    mask: Option<EdgeMask>,
+) {
+    let src = blocks.to_vec();
+    let bits = block_bits::<R>();
+    let whole = rhs / bits;
+    let by = rhs % bits;
+    let block_at = |i: Option<usize>| match i {
+        Some(i) if i < src.len() => src[i],
+        _ => R::zero(),
+    };
+
+    parallel::for_each_chunk(blocks, |start, chunk| {
+        for (offset, block) in chunk.iter_mut().enumerate() {
+            let idx = start + offset;
+            let (near, far) = match direction {
+                Shift::Left => (idx.checked_sub(whole), idx.checked_sub(whole + 1)),
+                Shift::Right => (idx.checked_add(whole), idx.checked_add(whole + 1)),
+            };
+
+            *block = direction.shift(block_at(near), by);
+            if by > 0 {
+                *block |= direction.back_shift(block_at(far), bits - by);
+            }
+            if let Some(m) = mask {
+                *block &= edge_mask(m, idx, width);
             }
         }
-    }
+    });
+}
 
-    // Convenience function for enumerating the blocks correctly during shifts
-    unsafe fn enumerate_blocks(&mut self, dir: Shift, mut op: impl FnMut(usize, *mut R)) {
-        match dir {
-            Shift::Left => {
-                for (i, block) in self.block_iter_mut().enumerate() {
-                    op(i, block);
-                }
+// Convenience function for enumerating the blocks correctly during shifts
+#[cfg(not(all(feature = "simd", target_endian = "little")))]
+fn enumerate_blocks<R: PrimUInt>(blocks: &mut [R], dir: Shift, mut op: impl FnMut(usize, &mut R)) {
+    match dir {
+        Shift::Left => {
+            for (i, block) in blocks.iter_mut().enumerate() {
+                op(i, block);
             }
-            Shift::Right => {
-                for (i, block) in self.block_iter_mut().rev().enumerate() {
-                    op(Self::REQUIRED_BLOCKS - i - 1, block);
-                }
+        }
+        Shift::Right => {
+            for (i, block) in blocks.iter_mut().enumerate().rev() {
+                op(i, block);
             }
         }
     }
+}
 
-    // Performs a shift on a single block, returning the bits that would be lost
-    unsafe fn shift_block(dir: Shift, by: usize, prev_lost: R, block: *mut R, mask: R) -> R {
-        if by >= Self::BLOCK_SIZE_BITS {
-            let lost = *block;
-            *block = prev_lost;
-            if by == Self::BLOCK_SIZE_BITS {
-                *block &= mask;
-            }
-            lost
-        } else {
-            let lost = dir.back_shift(*block, Self::BLOCK_SIZE_BITS - by);
-            *block = dir.shift(*block, by);
-            *block |= prev_lost;
+// Performs a shift on a single block, returning the bits that would be lost
+#[cfg(not(all(feature = "simd", target_endian = "little")))]
+fn shift_block<R: PrimUInt>(dir: Shift, by: usize, prev_lost: R, block: &mut R, mask: R) -> R {
+    let bits = block_bits::<R>();
+    if by >= bits {
+        let lost = *block;
+        *block = prev_lost;
+        if by == bits {
             *block &= mask;
-            lost
         }
+        lost
+    } else {
+        let lost = dir.back_shift(*block, bits - by);
+        *block = dir.shift(*block, by);
+        *block |= prev_lost;
+        *block &= mask;
+        lost
     }
+}
 
-    // Calculates the mask for a block at a given index
-    // Works by figuring out the bits that are width away from % N
-    fn edge_mask(mask: EdgeMask, block_idx: usize) -> R {
-        !(0..Self::BLOCK_SIZE_BITS)
-            .into_iter()
-            .filter(|i| match mask {
-                EdgeMask::Left(width) => {
-                    (((Self::BLOCK_SIZE_BITS) * block_idx) + i) % N::USIZE < width
-                }
-                EdgeMask::Right(width) => {
-                    N::USIZE - ((((Self::BLOCK_SIZE_BITS) * block_idx) + i) % N::USIZE) - 1 < width
-                }
-            })
-            .fold(R::zero(), |a, b| a | R::one() << b)
-    }
+// Calculates the mask for a block at a given index
+// Only the masked columns of the rows the block overlaps are visited, not every bit
+fn edge_mask<R: PrimUInt>(mask: EdgeMask, block_idx: usize, board_width: usize) -> R {
+    let bits = block_bits::<R>();
+    let start = bits * block_idx;
+    let columns = match mask {
+        EdgeMask::Left(width) => 0..width,
+        EdgeMask::Right(width) => board_width - width..board_width,
+    };
 
-    unsafe fn reset(&mut self) {
-        self.block_iter_mut().for_each(|block| *block = R::zero());
-    }
+    let rows = start / board_width..=(start + bits - 1) / board_width;
+    !rows
+        .flat_map(|row| columns.clone().map(move |col| row * board_width + col))
+        .filter(|pos| (start..start + bits).contains(pos))
+        .fold(R::zero(), |a, pos| a | R::one() << (pos - start))
 }
@@ -0,0 +1,345 @@
+// SIMD kernels for the block-wise operations on BitBoards.
+//
+// Every kernel works on the raw bytes of the block storage, which keeps them independent
+// of the block type R. Bitwise ops don't care how the bytes are grouped into blocks, and
+// neither does popcount since the padding bits after the board are always kept zeroed.
+// Shifts treat the bytes as one little endian number, so they're only used on little
+// endian targets where that's also how the blocks are laid out.
+//
+// On x86_64 the AVX2 kernels are picked at runtime when the CPU supports them, otherwise
+// SSE2 ( which every x86_64 CPU has ) is used. Everything else falls back to `scalar`.
+
+use std::mem;
+use std::slice;
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+pub(crate) fn bytes<R>(blocks: &[R]) -> &[u8] {
+    unsafe { slice::from_raw_parts(blocks.as_ptr() as *const u8, mem::size_of_val(blocks)) }
+}
+
+pub(crate) fn bytes_mut<R>(blocks: &mut [R]) -> &mut [u8] {
+    unsafe { slice::from_raw_parts_mut(blocks.as_mut_ptr() as *mut u8, mem::size_of_val(blocks)) }
+}
+
+pub(crate) fn and_assign(dst: &mut [u8], src: &[u8]) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        if is_x86_feature_detected!("avx2") {
+            return avx2::and_assign(dst, src);
+        }
+        sse2::and_assign(dst, src)
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    scalar::and_assign(dst, src)
+}
+
+pub(crate) fn or_assign(dst: &mut [u8], src: &[u8]) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        if is_x86_feature_detected!("avx2") {
+            return avx2::or_assign(dst, src);
+        }
+        sse2::or_assign(dst, src)
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    scalar::or_assign(dst, src)
+}
+
+pub(crate) fn xor_assign(dst: &mut [u8], src: &[u8]) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        if is_x86_feature_detected!("avx2") {
+            return avx2::xor_assign(dst, src);
+        }
+        sse2::xor_assign(dst, src)
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    scalar::xor_assign(dst, src)
+}
+
+pub(crate) fn count_ones(src: &[u8]) -> usize {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        if is_x86_feature_detected!("avx2") {
+            return avx2::count_ones(src);
+        }
+    }
+
+    scalar::count_ones(src)
+}
+
+// Shifts every bit towards the end of the slice by `by`, which must be 1 to 7. Whole bytes
+// are moved with copy_within before this.
+pub(crate) fn shl_bits(bytes: &mut [u8], by: u32) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        if is_x86_feature_detected!("avx2") {
+            return avx2::shl_bits(bytes, by);
+        }
+        sse2::shl_bits(bytes, by)
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    scalar::shl_bits(bytes, by)
+}
+
+// Shifts every bit towards the start of the slice by `by`, which must be 1 to 7
+pub(crate) fn shr_bits(bytes: &mut [u8], by: u32) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        if is_x86_feature_detected!("avx2") {
+            return avx2::shr_bits(bytes, by);
+        }
+        sse2::shr_bits(bytes, by)
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    scalar::shr_bits(bytes, by)
+}
+
+// Reference implementations. These are also what the vector kernels use for the bytes
+// left over after the last full register.
+pub(crate) mod scalar {
+    pub(crate) fn and_assign(dst: &mut [u8], src: &[u8]) {
+        dst.iter_mut().zip(src).for_each(|(l, r)| *l &= r);
+    }
+
+    pub(crate) fn or_assign(dst: &mut [u8], src: &[u8]) {
+        dst.iter_mut().zip(src).for_each(|(l, r)| *l |= r);
+    }
+
+    pub(crate) fn xor_assign(dst: &mut [u8], src: &[u8]) {
+        dst.iter_mut().zip(src).for_each(|(l, r)| *l ^= r);
+    }
+
+    // The x86_64 kernels do their own carry into the bytes after the last register
+    #[cfg_attr(target_arch = "x86_64", allow(dead_code))]
+    pub(crate) fn shl_bits(bytes: &mut [u8], by: u32) {
+        for i in (0..bytes.len()).rev() {
+            let carry = if i > 0 { bytes[i - 1] >> (8 - by) } else { 0 };
+            bytes[i] = bytes[i] << by | carry;
+        }
+    }
+
+    pub(crate) fn shr_bits(bytes: &mut [u8], by: u32) {
+        for i in 0..bytes.len() {
+            let carry = bytes.get(i + 1).map_or(0, |b| b << (8 - by));
+            bytes[i] = bytes[i] >> by | carry;
+        }
+    }
+
+    pub(crate) fn count_ones(src: &[u8]) -> usize {
+        let chunks = src.chunks_exact(8);
+        let rest: usize = chunks
+            .remainder()
+            .iter()
+            .map(|b| b.count_ones() as usize)
+            .sum();
+        chunks
+            .map(|c| {
+                let mut word = [0u8; 8];
+                word.copy_from_slice(c);
+                u64::from_ne_bytes(word).count_ones() as usize
+            })
+            .sum::<usize>()
+            + rest
+    }
+}
+
+// Generates a bitwise kernel that walks both slices a register at a time
+#[cfg(target_arch = "x86_64")]
+macro_rules! bitwise_kernel {
+    ($name:ident, $feature:literal, $width:expr, $load:ident, $store:ident, $op:ident) => {
+        #[target_feature(enable = $feature)]
+        pub(crate) unsafe fn $name(dst: &mut [u8], src: &[u8]) {
+            let len = std::cmp::min(dst.len(), src.len());
+            let whole = len - len % $width;
+
+            let mut i = 0;
+            while i < whole {
+                let l = $load(dst.as_ptr().add(i) as *const _);
+                let r = $load(src.as_ptr().add(i) as *const _);
+                $store(dst.as_mut_ptr().add(i) as *mut _, $op(l, r));
+                i += $width;
+            }
+
+            super::scalar::$name(&mut dst[whole..len], &src[whole..len]);
+        }
+    };
+}
+
+// Generates the shift kernels. Each register is shifted as 64-bit lanes and the carried
+// bits come from a second load one byte along, so the lanes never need to talk to each
+// other. Working from the far end means every byte is read before it's overwritten.
+#[cfg(target_arch = "x86_64")]
+macro_rules! shift_kernels {
+    ($feature:literal, $width:expr, $load:ident, $store:ident, $or:ident, $sll:ident, $srl:ident) => {
+        #[target_feature(enable = $feature)]
+        pub(crate) unsafe fn shl_bits(bytes: &mut [u8], by: u32) {
+            if bytes.is_empty() {
+                return;
+            }
+            let (own, carried) = (
+                _mm_cvtsi32_si128(by as i32),
+                _mm_cvtsi32_si128(8 - by as i32),
+            );
+            let whole = (bytes.len() - 1) / $width * $width;
+
+            // Registers start at the second byte since the first has nothing to carry in
+            for i in (whole + 1..bytes.len()).rev() {
+                bytes[i] = bytes[i] << by | bytes[i - 1] >> (8 - by);
+            }
+            let ptr = bytes.as_mut_ptr();
+            let mut i = whole;
+            while i > 0 {
+                i -= $width;
+                let v = $load(ptr.add(i + 1) as *const _);
+                let carry = $load(ptr.add(i) as *const _);
+                $store(
+                    ptr.add(i + 1) as *mut _,
+                    $or($sll(v, own), $srl(carry, carried)),
+                );
+            }
+            bytes[0] <<= by;
+        }
+
+        #[target_feature(enable = $feature)]
+        pub(crate) unsafe fn shr_bits(bytes: &mut [u8], by: u32) {
+            if bytes.is_empty() {
+                return;
+            }
+            let (own, carried) = (
+                _mm_cvtsi32_si128(by as i32),
+                _mm_cvtsi32_si128(8 - by as i32),
+            );
+            let whole = (bytes.len() - 1) / $width * $width;
+
+            // Registers stop short of the last byte since it has nothing to carry in
+            let ptr = bytes.as_mut_ptr();
+            let mut i = 0;
+            while i < whole {
+                let v = $load(ptr.add(i) as *const _);
+                let carry = $load(ptr.add(i + 1) as *const _);
+                $store(
+                    ptr.add(i) as *mut _,
+                    $or($srl(v, own), $sll(carry, carried)),
+                );
+                i += $width;
+            }
+            super::scalar::shr_bits(&mut bytes[whole..], by);
+        }
+    };
+}
+
+#[cfg(target_arch = "x86_64")]
+pub(crate) mod sse2 {
+    use super::*;
+
+    bitwise_kernel!(
+        and_assign,
+        "sse2",
+        16,
+        _mm_loadu_si128,
+        _mm_storeu_si128,
+        _mm_and_si128
+    );
+    bitwise_kernel!(
+        or_assign,
+        "sse2",
+        16,
+        _mm_loadu_si128,
+        _mm_storeu_si128,
+        _mm_or_si128
+    );
+    bitwise_kernel!(
+        xor_assign,
+        "sse2",
+        16,
+        _mm_loadu_si128,
+        _mm_storeu_si128,
+        _mm_xor_si128
+    );
+    shift_kernels!(
+        "sse2",
+        16,
+        _mm_loadu_si128,
+        _mm_storeu_si128,
+        _mm_or_si128,
+        _mm_sll_epi64,
+        _mm_srl_epi64
+    );
+}
+
+#[cfg(target_arch = "x86_64")]
+pub(crate) mod avx2 {
+    use super::*;
+
+    bitwise_kernel!(
+        and_assign,
+        "avx2",
+        32,
+        _mm256_loadu_si256,
+        _mm256_storeu_si256,
+        _mm256_and_si256
+    );
+    bitwise_kernel!(
+        or_assign,
+        "avx2",
+        32,
+        _mm256_loadu_si256,
+        _mm256_storeu_si256,
+        _mm256_or_si256
+    );
+    bitwise_kernel!(
+        xor_assign,
+        "avx2",
+        32,
+        _mm256_loadu_si256,
+        _mm256_storeu_si256,
+        _mm256_xor_si256
+    );
+    shift_kernels!(
+        "avx2",
+        32,
+        _mm256_loadu_si256,
+        _mm256_storeu_si256,
+        _mm256_or_si256,
+        _mm256_sll_epi64,
+        _mm256_srl_epi64
+    );
+
+    // Nibble lookup popcount. Each byte is split into two nibbles which are counted with a
+    // shuffle against a 16 entry table, then the byte counts are summed into 64-bit lanes.
+    #[target_feature(enable = "avx2")]
+    pub(crate) unsafe fn count_ones(src: &[u8]) -> usize {
+        let lookup = _mm256_setr_epi8(
+            0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, //
+            0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4,
+        );
+        let low_nibble = _mm256_set1_epi8(0x0f);
+        let whole = src.len() - src.len() % 32;
+
+        let mut acc = _mm256_setzero_si256();
+        let mut i = 0;
+        while i < whole {
+            let v = _mm256_loadu_si256(src.as_ptr().add(i) as *const _);
+            let lo = _mm256_and_si256(v, low_nibble);
+            let hi = _mm256_and_si256(_mm256_srli_epi16(v, 4), low_nibble);
+            let counts = _mm256_add_epi8(
+                _mm256_shuffle_epi8(lookup, lo),
+                _mm256_shuffle_epi8(lookup, hi),
+            );
+            acc = _mm256_add_epi64(acc, _mm256_sad_epu8(counts, _mm256_setzero_si256()));
+            i += 32;
+        }
+
+        let mut lanes = [0u64; 4];
+        _mm256_storeu_si256(lanes.as_mut_ptr() as *mut _, acc);
+        lanes.iter().sum::<u64>() as usize + super::scalar::count_ones(&src[whole..])
+    }
+}
@@ -0,0 +1,158 @@
+use super::*;
+use std::ops::{Index, Shl, ShlAssign};
+
+// A stack of BitBoards adding a third dimension to a board, e.g for Tak.
+// Layer 0 is the bottom of the stack, and each layer holds the cells that have a piece at
+// that height. A cell's pieces are expected to be contiguous from the bottom up.
+#[derive(Clone, Debug)]
+pub struct BitBoardStack<N: Unsigned, R: PrimUInt = u64> {
+    layers: Vec<BitBoard<N, R>>,
+}
+
+impl<N: Unsigned, R: PrimUInt> BitBoardStack<N, R> {
+    pub fn new() -> Self {
+        BitBoardStack { layers: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn push(&mut self, layer: BitBoard<N, R>) {
+        self.layers.push(layer);
+    }
+
+    pub fn pop(&mut self) -> Option<BitBoard<N, R>> {
+        self.layers.pop()
+    }
+
+    pub fn insert(&mut self, index: usize, layer: BitBoard<N, R>) {
+        self.layers.insert(index, layer);
+    }
+
+    pub fn remove(&mut self, index: usize) -> BitBoard<N, R> {
+        self.layers.remove(index)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&BitBoard<N, R>> {
+        self.layers.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut BitBoard<N, R>> {
+        self.layers.get_mut(index)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, BitBoard<N, R>> {
+        self.layers.iter()
+    }
+
+    // Collapses the stack to the cells set on any layer
+    pub fn union(&self) -> BitBoard<N, R> {
+        let mut result = BitBoard::default();
+        self.layers.iter().for_each(|layer| result |= layer);
+        result
+    }
+
+    // Collapses the stack to the cells set on every layer. An empty stack gives an empty board
+    pub fn intersection(&self) -> BitBoard<N, R> {
+        let mut layers = self.layers.iter();
+        match layers.next() {
+            Some(first) => layers.fold(first.clone(), |mut result, layer| {
+                result &= layer;
+                result
+            }),
+            None => BitBoard::default(),
+        }
+    }
+
+    // Number of layers that have the given cell set
+    pub fn height(&self, x: usize, y: usize) -> usize {
+        self.layers
+            .iter()
+            .filter(|layer| layer.is_set(x, y))
+            .count()
+    }
+
+    // The cells whose top piece is owned by a player.
+    // `owner` is a stack of the same shape as this one, with the player's pieces set on each
+    // layer. Working from the top down, a cell's top piece is on the first layer it's set on.
+    // Layers missing from `owner` count as empty, so a shorter owner stack owns nothing above
+    // its top layer.
+    pub fn top_owner(&self, owner: &Self) -> BitBoard<N, R> {
+        let mut covered = BitBoard::default();
+        let mut result = BitBoard::default();
+
+        for (i, layer) in self.layers.iter().enumerate().rev() {
+            let uncovered = !&covered;
+            if let Some(owned) = owner.layers.get(i) {
+                let top = layer & &uncovered;
+                result |= &(&top & owned);
+            }
+            covered |= layer;
+        }
+
+        result
+    }
+
+    fn shift_layers(&mut self, m: Move) {
+        self.layers.iter_mut().for_each(|layer| *layer <<= m);
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Default for BitBoardStack<N, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> From<Vec<BitBoard<N, R>>> for BitBoardStack<N, R> {
+    fn from(layers: Vec<BitBoard<N, R>>) -> Self {
+        BitBoardStack { layers }
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Index<usize> for BitBoardStack<N, R> {
+    type Output = BitBoard<N, R>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.layers[index]
+    }
+}
+
+impl<'a, N: Unsigned, R: PrimUInt> IntoIterator for &'a BitBoardStack<N, R> {
+    type Item = &'a BitBoard<N, R>;
+    type IntoIter = std::slice::Iter<'a, BitBoard<N, R>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.layers.iter()
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Shl<Move> for &BitBoardStack<N, R> {
+    type Output = BitBoardStack<N, R>;
+
+    fn shl(self, rhs: Move) -> Self::Output {
+        let mut result = self.clone();
+        result.shift_layers(rhs);
+        result
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> Shl<Move> for BitBoardStack<N, R> {
+    type Output = Self;
+
+    fn shl(mut self, rhs: Move) -> Self::Output {
+        self.shift_layers(rhs);
+        self
+    }
+}
+
+impl<N: Unsigned, R: PrimUInt> ShlAssign<Move> for BitBoardStack<N, R> {
+    fn shl_assign(&mut self, rhs: Move) {
+        self.shift_layers(rhs);
+    }
+}
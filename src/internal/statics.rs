@@ -2,20 +2,41 @@ use super::*;
 use std::alloc::Layout;
 use std::mem;
 
-const fn required_bits(board_size: usize, alignment_bits: usize) -> usize {
-    let remainder = board_size % alignment_bits;
+// Boards at least this big split their block operations across threads when the
+// `parallel` feature is enabled
+pub(super) const PARALLEL_THRESHOLD_BYTES: usize = 16 * 1024;
+
+pub(super) const fn required_bits(board_size: usize, block_bits: usize) -> usize {
+    let remainder = board_size % block_bits;
     let mul = (remainder != 0) as usize;
-    board_size + (alignment_bits - remainder) * mul
+    board_size + (block_bits - remainder) * mul
 }
 
-const fn required_bytes(required_bits: usize) -> usize {
-    let t = (required_bits % 8) != 0;
+pub(super) const fn required_bytes(required_bits: usize) -> usize {
+    let t = !required_bits.is_multiple_of(8);
     required_bits / 8 + (t as usize)
 }
 
-const fn required_blocks(bytes: usize, alignment: usize) -> usize {
-    let t = (bytes % alignment != 0) as usize;
-    (bytes / alignment) + t
+pub(super) const fn required_blocks(bytes: usize, block_size: usize) -> usize {
+    let t = !bytes.is_multiple_of(block_size) as usize;
+    (bytes / block_size) + t
+}
+
+// Number of R blocks needed to store a width x height board
+pub const fn required_blocks_for<R>(width: usize, height: usize) -> usize {
+    let block_size = mem::size_of::<R>();
+    let bits = required_bits(width * height, block_size * 8);
+    required_blocks(required_bytes(bits), block_size)
+}
+
+// Mask for the bits of the last block that are on the board. All ones when the board fills
+// the last block.
+pub(super) fn last_block_mask<R: PrimUInt>(board_size: usize) -> R {
+    let remainder = board_size % (mem::size_of::<R>() * 8);
+    match remainder {
+        0 => R::max_value(),
+        _ => (R::one() + R::one()).pow(remainder as u32) - R::one(),
+    }
 }
 
 impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
@@ -24,15 +45,26 @@ impl<N: Unsigned, R: PrimUInt> BitBoard<N, R> {
     pub const BLOCK_SIZE: usize = mem::size_of::<R>();
     pub const BLOCK_SIZE_BITS: usize = Self::BLOCK_SIZE * 8;
 
+    // Blocks are sized by size_of rather than align_of, which differ for u128 on some
+    // targets. The alignment is only used to lay out the allocation.
     pub const ALIGNMENT: usize = mem::align_of::<R>();
     pub const ALIGNMENT_BITS: usize = Self::ALIGNMENT * 8;
 
-    pub const REQUIRED_BITS: usize = required_bits(Self::BOARD_SIZE, Self::ALIGNMENT_BITS);
+    pub const REQUIRED_BITS: usize = required_bits(Self::BOARD_SIZE, Self::BLOCK_SIZE_BITS);
     pub const REQUIRED_BYTES: usize = required_bytes(Self::REQUIRED_BITS);
-    pub const REQUIRED_BLOCKS: usize = required_blocks(Self::REQUIRED_BYTES, Self::ALIGNMENT);
+    pub const REQUIRED_BLOCKS: usize = required_blocks(Self::REQUIRED_BYTES, Self::BLOCK_SIZE);
 
     pub const HAS_BLOCK_MASK: bool = Self::BOARD_SIZE % Self::BLOCK_SIZE_BITS != 0;
 
+    pub const PARALLEL: bool =
+        cfg!(feature = "parallel") && Self::REQUIRED_BYTES >= PARALLEL_THRESHOLD_BYTES;
+
+    pub(super) const SHAPE: Shape = Shape {
+        width: N::USIZE,
+        height: N::USIZE,
+        parallel: Self::PARALLEL,
+    };
+
     #[inline(always)]
     pub(super) fn last_block_mask() -> R {
         let remainder = Self::BOARD_SIZE % Self::BLOCK_SIZE_BITS;
@@ -1,6 +1,42 @@
 use crate::*;
 use typenum::*;
 
+// Small xorshift generator so the tests don't need a rand dependency
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+fn random_board<N: Unsigned, R: PrimUInt>(seed: u64) -> BitBoard<N, R> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut bb = BitBoard::default();
+    for y in 0..N::USIZE {
+        for x in 0..N::USIZE {
+            if xorshift(&mut state) & 1 == 1 {
+                bb.set(x, y);
+            }
+        }
+    }
+    bb
+}
+
+fn move_offset(m: Move) -> (isize, isize) {
+    use Move::*;
+    match m {
+        NullMove => (0, 0),
+        Left(i) => (-(i as isize), 0),
+        Right(i) => (i as isize, 0),
+        Up(i) => (0, i as isize),
+        Down(i) => (0, -(i as isize)),
+        UpLeft(u, l) => (-(l as isize), u as isize),
+        UpRight(u, r) => (r as isize, u as isize),
+        DownLeft(d, l) => (-(l as isize), -(d as isize)),
+        DownRight(d, r) => (r as isize, -(d as isize)),
+    }
+}
+
 macro_rules! test_suite {
     ($n:ident, $u:ident, $r:ty) => {
         mod $n {
@@ -127,6 +163,105 @@ macro_rules! test_suite {
                 }
             }
 
+            #[test]
+            fn not_works() {
+                let bb = random_board::<$u, $r>(3);
+                let inverted = !&bb;
+                assert_eq!(inverted.count_ones(), SIZE * SIZE - bb.count_ones());
+                assert_eq!((&inverted & &bb).count_ones(), 0);
+                assert_eq!((&!inverted ^ &bb).count_ones(), 0);
+            }
+
+            #[test]
+            fn shifts_match_cellwise() {
+                use Move::*;
+                let bb = random_board::<$u, $r>(SIZE as u64);
+                let far = SIZE - 1;
+                let moves = [
+                    Up(1),
+                    Down(1),
+                    Left(1),
+                    Right(1),
+                    Up(far),
+                    Down(far),
+                    Left(far),
+                    Right(far),
+                    UpLeft(1, far),
+                    UpRight(far, 1),
+                    DownLeft(1, far),
+                    DownRight(far, 1),
+                ];
+
+                for m in moves.iter() {
+                    let shifted = &bb << *m;
+                    let (dx, dy) = move_offset(*m);
+                    for x in 0..SIZE {
+                        for y in 0..SIZE {
+                            let (fx, fy) = (x as isize - dx, y as isize - dy);
+                            let expected =
+                                fx >= 0 && fy >= 0 && bb.is_set(fx as usize, fy as usize);
+                            assert_eq!(shifted.is_set(x, y), expected, "{:?} at {:?}", m, (x, y));
+                        }
+                    }
+                }
+            }
+
+            #[test]
+            fn dyn_board_matches_typed() {
+                use std::convert::TryFrom;
+                use Move::*;
+
+                let a = random_board::<$u, $r>(4);
+                let b = random_board::<$u, $r>(5);
+                let (da, db) = (DynBitBoard::from(&a), DynBitBoard::from(&b));
+                let same = |typed: &TestBoard, dynamic: &DynBitBoard<$r>| {
+                    assert_eq!(format!("{}", typed), format!("{}", dynamic));
+                    assert_eq!(typed.count_ones(), dynamic.count_ones());
+                };
+
+                same(&a, &da);
+                same(&(&a & &b), &(&da & &db));
+                same(&(&a | &b), &(&da | &db));
+                same(&(&a ^ &b), &(&da ^ &db));
+                same(&!&a, &!&da);
+                for m in [Up(1), Left(SIZE - 1), DownRight(1, 1), UpLeft(SIZE - 1, 1)].iter() {
+                    same(&(&a << *m), &(&da << *m));
+                }
+
+                let typed = a
+                    .moves()
+                    .translate(UpRight(1, 1))
+                    .rotate(Rotation::Clockwise)
+                    .mirror();
+                let dynamic = da
+                    .moves()
+                    .translate(UpRight(1, 1))
+                    .rotate(Rotation::Clockwise)
+                    .mirror();
+                same(&typed.collect(), &dynamic.collect());
+
+                let back = TestBoard::try_from(&da).unwrap();
+                assert_eq!((&back ^ &a).count_ones(), 0);
+            }
+
+            #[test]
+            fn ops_match_cellwise() {
+                let a = random_board::<$u, $r>(1);
+                let b = random_board::<$u, $r>(2);
+                let (and, or, xor) = (&a & &b, &a | &b, &a ^ &b);
+
+                let mut ones = 0;
+                for x in 0..SIZE {
+                    for y in 0..SIZE {
+                        let (l, r) = (a.is_set(x, y), b.is_set(x, y));
+                        assert_eq!(and.is_set(x, y), l && r);
+                        assert_eq!(or.is_set(x, y), l || r);
+                        assert_eq!(xor.is_set(x, y), l != r);
+                        ones += l as usize;
+                    }
+                }
+                assert_eq!(a.count_ones(), ones);
+            }
         }
     };
 }
@@ -171,3 +306,2153 @@ test_suite!(u7_u64, U7, u64);
 test_suite!(u8_u64, U8, u64);
 test_suite!(u20_u64, U20, u64);
 test_suite!(u100_u64, U100, u64);
+// Big enough to go over the parallel threshold
+test_suite!(u400_u64, U400, u64);
+
+test_suite!(u2_u128, U2, u128);
+test_suite!(u3_u128, U3, u128);
+test_suite!(u8_u128, U8, u128);
+test_suite!(u11_u128, U11, u128);
+test_suite!(u12_u128, U12, u128);
+test_suite!(u20_u128, U20, u128);
+test_suite!(u100_u128, U100, u128);
+
+#[test]
+fn u128_blocks_hold_11x11() {
+    assert_eq!(BitBoard11x11::REQUIRED_BLOCKS, 1);
+    assert_eq!(BitBoard11x11::REQUIRED_BYTES, 16);
+    assert_eq!(BitBoard::<U12, u128>::REQUIRED_BLOCKS, 2);
+
+    let corner = BitBoard11x11::new(vec![(10, 10)]);
+    assert_eq!((&corner << Move::DownLeft(10, 10)).count_ones(), 1);
+    assert_eq!(ConstBitBoard11x11::from(&corner).count_ones(), 1);
+}
+
+mod stack {
+    use super::*;
+
+    type Stack = BitBoardStack<U5, u32>;
+
+    fn layer(cells: Vec<(usize, usize)>) -> BitBoard5x5 {
+        BitBoard5x5::new(cells)
+    }
+
+    #[test]
+    fn push_pop_insert_remove_work() {
+        let mut stack = Stack::new();
+        assert!(stack.is_empty());
+
+        stack.push(layer(vec![(0, 0)]));
+        stack.push(layer(vec![(1, 1)]));
+        stack.insert(1, layer(vec![(2, 2)]));
+        assert_eq!(stack.len(), 3);
+        assert!(stack[1].is_set(2, 2));
+
+        let removed = stack.remove(0);
+        assert!(removed.is_set(0, 0));
+        assert!(stack.pop().unwrap().is_set(1, 1));
+        assert_eq!(stack.len(), 1);
+        assert!(stack.get(1).is_none());
+    }
+
+    #[test]
+    fn union_and_intersection_work() {
+        let stack = Stack::from(vec![
+            layer(vec![(0, 0), (1, 1), (2, 2)]),
+            layer(vec![(0, 0), (1, 1)]),
+            layer(vec![(0, 0), (4, 4)]),
+        ]);
+
+        let union = stack.union();
+        assert_eq!(union.count_ones(), 4);
+        assert!(union.is_set(4, 4));
+
+        let intersection = stack.intersection();
+        assert_eq!(intersection.count_ones(), 1);
+        assert!(intersection.is_set(0, 0));
+
+        assert_eq!(Stack::new().intersection().count_ones(), 0);
+    }
+
+    #[test]
+    fn moves_apply_to_every_layer() {
+        let mut stack = Stack::from(vec![layer(vec![(0, 0), (4, 0)]), layer(vec![(0, 0)])]);
+        stack <<= Move::UpRight(1, 1);
+
+        assert!(stack[0].is_set(1, 1));
+        assert_eq!(stack[0].count_ones(), 1);
+        assert!(stack[1].is_set(1, 1));
+    }
+
+    #[test]
+    fn height_works() {
+        let stack = Stack::from(vec![
+            layer(vec![(0, 0), (3, 2)]),
+            layer(vec![(0, 0)]),
+            layer(vec![(0, 0)]),
+        ]);
+
+        assert_eq!(stack.height(0, 0), 3);
+        assert_eq!(stack.height(3, 2), 1);
+        assert_eq!(stack.height(4, 4), 0);
+    }
+
+    #[test]
+    fn top_owner_works() {
+        // (0, 0) is white, black, white from the bottom up, (1, 0) is white under black
+        // and (2, 0) is a lone black piece
+        let pieces = Stack::from(vec![
+            layer(vec![(0, 0), (1, 0), (2, 0)]),
+            layer(vec![(0, 0), (1, 0)]),
+            layer(vec![(0, 0)]),
+        ]);
+        let white = Stack::from(vec![
+            layer(vec![(0, 0), (1, 0)]),
+            layer(vec![]),
+            layer(vec![(0, 0)]),
+        ]);
+        let black = Stack::from(vec![
+            layer(vec![(2, 0)]),
+            layer(vec![(0, 0), (1, 0)]),
+            layer(vec![]),
+        ]);
+
+        let white_tops = pieces.top_owner(&white);
+        assert_eq!(white_tops.count_ones(), 1);
+        assert!(white_tops.is_set(0, 0));
+
+        let black_tops = pieces.top_owner(&black);
+        assert_eq!(black_tops.count_ones(), 2);
+        assert!(black_tops.is_set(1, 0) && black_tops.is_set(2, 0));
+    }
+
+    #[test]
+    fn top_owner_treats_missing_layers_as_empty() {
+        // (0, 0) is white under black, but the owner stack only has the bottom layer
+        let pieces = Stack::from(vec![layer(vec![(0, 0), (1, 0)]), layer(vec![(0, 0)])]);
+        let white = Stack::from(vec![layer(vec![(0, 0), (1, 0)])]);
+
+        let white_tops = pieces.top_owner(&white);
+        assert_eq!(white_tops.count_ones(), 1);
+        assert!(white_tops.is_set(1, 0));
+
+        let taller = Stack::from(vec![
+            layer(vec![]),
+            layer(vec![(0, 0)]),
+            layer(vec![(1, 0)]),
+        ]);
+        let tops = pieces.top_owner(&taller);
+        assert_eq!(tops.count_ones(), 1);
+        assert!(tops.is_set(0, 0));
+    }
+}
+
+mod dynamic {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn size_is_chosen_at_runtime() {
+        for &size in [9, 13, 19].iter() {
+            let mut go = DynBitBoard::<u64>::new(size, vec![(0, 0), (size - 1, size - 1)]);
+            assert_eq!(go.size(), size);
+            assert!(go.is_set(size - 1, size - 1));
+            assert!(!go.is_set(size, 0));
+
+            go.unset(0, 0);
+            go <<= Move::Left(1);
+            assert_eq!(go.count_ones(), 1);
+            assert!(go.is_set(size - 2, size - 1));
+        }
+    }
+
+    #[test]
+    fn conversion_checks_size() {
+        let dynamic = DynBitBoard::<u64>::new(9, vec![(4, 4)]);
+        assert_eq!(
+            BitBoard8x8::try_from(&dynamic).unwrap_err(),
+            SizeMismatch {
+                expected: 8,
+                found: 9
+            }
+        );
+        assert!(BitBoard::<U9, u64>::try_from(dynamic).unwrap().is_set(4, 4));
+    }
+
+    #[test]
+    fn display_matches_typed() {
+        let bb = random_board::<U12, u32>(5);
+        assert_eq!(DynBitBoard::from(&bb).to_string(), bb.to_string());
+    }
+
+    #[test]
+    #[should_panic]
+    fn mismatched_ops_panic() {
+        let _ = &DynBitBoard::<u64>::empty(9) | &DynBitBoard::<u64>::empty(13);
+    }
+}
+
+mod convert {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn cells<N: Unsigned, R: PrimUInt>(board: &BitBoard<N, R>) -> Vec<(usize, usize)> {
+        (0..N::USIZE)
+            .flat_map(|y| (0..N::USIZE).map(move |x| (x, y)))
+            .filter(|&(x, y)| board.is_set(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn convert_keeps_cells() {
+        for seed in 1..10 {
+            let compact = random_board::<U13, u8>(seed);
+            let wide: BitBoard<U13, u64> = compact.convert();
+            let widest = BitBoard::<U13, u128>::from(&wide);
+            let back = BitBoard::<U13, u16>::from(widest);
+
+            assert_eq!(cells(&wide), cells(&compact));
+            assert_eq!(cells(&back), cells(&compact));
+            assert_eq!(cells(&back.convert::<u8>()), cells(&compact));
+        }
+    }
+
+    #[test]
+    fn blocks_round_trip() {
+        let board = BitBoard::<U5, u8>::new(vec![(0, 0), (2, 1), (4, 4)]);
+        let blocks = board.to_blocks();
+        assert_eq!(blocks, vec![0b1000_0001, 0, 0, 1]);
+
+        let copy = BitBoard::<U5, u8>::from_blocks(&blocks).unwrap();
+        assert_eq!(cells(&copy), cells(&board));
+    }
+
+    #[test]
+    fn from_blocks_checks_input() {
+        assert_eq!(
+            BitBoard::<U5, u8>::from_blocks(&[0, 0]).unwrap_err(),
+            BlocksError::WrongLength {
+                expected: 4,
+                found: 2
+            }
+        );
+        assert_eq!(
+            BitBoard::<U5, u8>::from_blocks(&[0, 0, 0, 0b10]).unwrap_err(),
+            BlocksError::OutOfBounds
+        );
+        assert!(BitBoard8x8::from_blocks(&[u64::MAX]).is_ok());
+    }
+
+    #[test]
+    fn raw_integers_work() {
+        let board = BitBoard8x8::new(vec![(0, 0), (7, 7)]);
+        assert_eq!(board.as_raw(), 1 | 1 << 63);
+        assert_eq!(u64::from(&board), 1 | 1 << 63);
+        assert_eq!(
+            cells(&BitBoard8x8::from_raw(board.as_raw()).unwrap()),
+            cells(&board)
+        );
+
+        let small = BitBoard5x5::try_from(1u32 << 24).unwrap();
+        assert!(small.is_set(4, 4));
+        assert_eq!(u32::from(small), 1 << 24);
+        assert_eq!(
+            BitBoard5x5::try_from(1u32 << 25).unwrap_err(),
+            BlocksError::OutOfBounds
+        );
+    }
+}
+
+mod parse {
+    use super::*;
+
+    #[test]
+    fn display_round_trips() {
+        for seed in 1..20 {
+            let board = random_board::<U9, u32>(seed);
+            let parsed: BitBoard<U9, u32> = format!("{}", board).parse().unwrap();
+            assert_eq!(format!("{}", parsed), format!("{}", board));
+        }
+    }
+
+    #[test]
+    fn grids_with_labels_parse() {
+        let board: BitBoard<U4, u16> = "
+              a b c d
+            4 . . . x 4
+            3 . # . . 3
+            2 . . . . 2
+            1 x . . . 1
+              a b c d
+        "
+        .parse()
+        .unwrap();
+
+        assert_eq!(board.count_ones(), 3);
+        assert!(board.is_set(3, 3));
+        assert!(board.is_set(1, 2));
+        assert!(board.is_set(0, 0));
+
+        let compact: BitBoard<U4, u16> = "...x\n.#..\n....\nx...".parse().unwrap();
+        assert_eq!(format!("{}", compact), format!("{}", board));
+
+        let ranked: BitBoard<U10, u64> = (1..=10)
+            .rev()
+            .map(|r| format!("{:2} 1 0 0 0 0 0 0 0 0 1", r))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .parse()
+            .unwrap();
+        assert_eq!(ranked.count_ones(), 20);
+    }
+
+    #[test]
+    fn trailing_labels_alone_parse() {
+        let board: BitBoard3x3 = "1 0 1 3\n0 1 0 2\n0 0 1 1".parse().unwrap();
+        assert_eq!(board.count_ones(), 4);
+        assert!(board.is_set(0, 2) && board.is_set(2, 2));
+        assert!(board.is_set(1, 1) && board.is_set(2, 0));
+
+        let leading: BitBoard3x3 = "3 1 0 1\n2 0 1 0\n1 0 0 1".parse().unwrap();
+        assert_eq!(format!("{}", leading), format!("{}", board));
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        let err = "...\n.o.\n...".parse::<BitBoard3x3>().unwrap_err();
+        assert_eq!(
+            err,
+            ParseError {
+                line: 2,
+                column: 2,
+                kind: ParseErrorKind::UnexpectedChar('o')
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 2: unexpected character 'o'"
+        );
+
+        assert_eq!(
+            "...\n..\n...".parse::<BitBoard3x3>().unwrap_err().kind,
+            ParseErrorKind::WrongWidth {
+                expected: 3,
+                found: 2
+            }
+        );
+        assert_eq!(
+            "...\n...".parse::<BitBoard3x3>().unwrap_err(),
+            ParseError {
+                line: 3,
+                column: 1,
+                kind: ParseErrorKind::WrongHeight {
+                    expected: 3,
+                    found: 2
+                }
+            }
+        );
+        assert_eq!(
+            "...\n...\n...\n...\n"
+                .parse::<BitBoard3x3>()
+                .unwrap_err()
+                .line,
+            4
+        );
+    }
+}
+
+mod literal {
+    use super::*;
+
+    #[test]
+    fn macro_matches_new() {
+        let glider: BitBoard<U5, u32> = bitboard![
+            ". x . . .",
+            ". . x . .",
+            "x x x . .",
+            ". . . . .",
+            ". . . . .",
+        ];
+        let expected = BitBoard::<U5, u32>::new(vec![(1, 4), (2, 3), (0, 2), (1, 2), (2, 2)]);
+        assert_eq!(format!("{}", glider), format!("{}", expected));
+    }
+
+    #[test]
+    fn size_is_inferred_with_block_type() {
+        let blinker = bitboard![u16; "...", "xxx", "..."];
+        let _: &BitBoard3x3 = &blinker;
+        assert_eq!(blinker.count_ones(), 3);
+        assert!(blinker.is_set(0, 1) && blinker.is_set(2, 1));
+
+        let wide = bitboard![u64;
+            "#.......",
+            "........",
+            "........",
+            "........",
+            "........",
+            "........",
+            "........",
+            ".......1",
+        ];
+        assert_eq!(wide.as_raw(), 1 << 7 | 1 << 56);
+    }
+}
+
+mod render {
+    use super::*;
+
+    #[test]
+    fn plain_render_matches_display_layout() {
+        let board = BitBoard3x3::new(vec![(0, 0), (2, 2)]);
+        assert_eq!(
+            board.renderer().to_string(),
+            ". . x \n\
+             . . . \n\
+             x . . \n"
+        );
+        assert_eq!(
+            board
+                .renderer()
+                .origin(Origin::TopLeft)
+                .empty('0')
+                .to_string(),
+            "x 0 0 \n\
+             0 0 0 \n\
+             0 0 x \n"
+        );
+    }
+
+    #[test]
+    fn overlays_and_labels_work() {
+        let white = BitBoard4x4::new(vec![(0, 0), (1, 0)]);
+        let black = BitBoard4x4::new(vec![(3, 3)]);
+        let attacked = BitBoard4x4::new(vec![(1, 0), (1, 1)]);
+
+        let text = Renderer::new()
+            .labels(Labels::Algebraic)
+            .overlay("white", &white, 'W')
+            .overlay("black", &black, 'B')
+            .overlay("attacked", &attacked, '*')
+            .to_string();
+        assert_eq!(
+            text,
+            "4 . . . B \n\
+             3 . . . . \n\
+             2 . * . . \n\
+             1 W * . . \n  \
+             a b c d \n\
+             W white\n\
+             B black\n\
+             * attacked\n"
+        );
+    }
+
+    #[test]
+    fn numeric_labels_pad_cells() {
+        let board = BitBoard::<U11, u128>::new(vec![(10, 0)]);
+        let text = board.renderer().labels(Labels::Numeric).to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[10], " 0 .  .  .  .  .  .  .  .  .  .  x  ");
+        assert_eq!(lines[11], "   0  1  2  3  4  5  6  7  8  9  10 ");
+    }
+
+    #[test]
+    fn ansi_colours_are_optional() {
+        let board = BitBoard3x3::new(vec![(1, 1)]);
+        let glyph = Glyph::new('o').colour(Colour::Red);
+        let plain = Renderer::new().overlay("", &board, glyph).to_string();
+        let coloured = Renderer::new()
+            .overlay("", &board, glyph)
+            .ansi(true)
+            .to_string();
+
+        assert!(!plain.contains('\x1b'));
+        assert!(coloured.contains("\x1b[31mo\x1b[0m"));
+    }
+
+    #[test]
+    fn file_labels_run_past_z() {
+        let board = BitBoard::<U28, u64>::default();
+        let text = board.renderer().labels(Labels::Algebraic).to_string();
+        assert!(text.lines().last().unwrap().ends_with("y  z  aa ab "));
+    }
+}
+
+mod image {
+    use super::*;
+
+    #[test]
+    fn pbm_round_trips() {
+        for &format in [PbmFormat::Plain, PbmFormat::Binary].iter() {
+            for seed in 1..10 {
+                let board = random_board::<U50, u64>(seed);
+                let mut bytes = Vec::new();
+                board.write_pbm(&mut bytes, format).unwrap();
+
+                let read = BitBoard::<U50, u64>::read_pbm(&bytes[..]).unwrap();
+                assert_eq!(format!("{}", read), format!("{}", board));
+            }
+        }
+    }
+
+    #[test]
+    fn pbm_matches_the_spec() {
+        let board = BitBoard3x3::new(vec![(0, 2), (2, 0)]);
+        let mut plain = Vec::new();
+        board.write_pbm(&mut plain, PbmFormat::Plain).unwrap();
+        assert_eq!(plain, b"P1\n3 3\n1 0 0\n0 0 0\n0 0 1\n");
+
+        let mut binary = Vec::new();
+        board.write_pbm(&mut binary, PbmFormat::Binary).unwrap();
+        assert_eq!(binary, b"P4\n3 3\n\x80\x00\x20");
+
+        let commented = b"P1\n# a comment\n3 3\n100\n000 # another\n001";
+        let read = BitBoard3x3::read_pbm(&commented[..]).unwrap();
+        assert_eq!(format!("{}", read), format!("{}", board));
+    }
+
+    #[test]
+    fn pbm_errors_work() {
+        let read = |data: &[u8]| BitBoard3x3::read_pbm(data).unwrap_err();
+        assert!(matches!(read(b"P2\n3 3\n"), PbmError::BadMagic));
+        assert!(matches!(read(b"P1\nthree 3\n"), PbmError::BadHeader));
+        assert!(matches!(
+            read(b"P1\n4 3\n"),
+            PbmError::SizeMismatch {
+                expected: 3,
+                width: 4,
+                height: 3
+            }
+        ));
+        assert!(matches!(read(b"P1\n3 3\n1 0 2"), PbmError::BadPixel('2')));
+        assert!(matches!(read(b"P1\n3 3\n1 0"), PbmError::Truncated));
+        assert!(matches!(read(b"P4\n3 3\n\x80"), PbmError::Truncated));
+    }
+
+    #[test]
+    fn svg_fills_cells() {
+        let board = BitBoard::<U2, u8>::new(vec![(0, 0)]);
+        let svg = board.to_svg(10);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\""));
+        assert!(svg.contains("<rect x=\"0\" y=\"10\" width=\"10\" height=\"10\" fill=\"black\""));
+        assert_eq!(svg.matches("fill=\"white\"").count(), 3);
+        assert!(svg.ends_with("</svg>\n"));
+
+        let other = BitBoard::<U2, u8>::new(vec![(1, 1)]);
+        let svg = Renderer::new()
+            .overlay("a", &board, Glyph::new('a').colour(Colour::Red))
+            .overlay("b", &other, Glyph::new('b').colour(Colour::Blue))
+            .origin(Origin::TopLeft)
+            .to_svg(5);
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"5\" height=\"5\" fill=\"red\""));
+        assert!(svg.contains("<rect x=\"5\" y=\"5\" width=\"5\" height=\"5\" fill=\"blue\""));
+    }
+}
+
+mod binary {
+    use super::*;
+
+    fn written<N: Unsigned, R: PrimUInt>(board: &BitBoard<N, R>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        board.write_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn round_trip<N: Unsigned, R: PrimUInt>(board: &BitBoard<N, R>) -> BitBoard<N, R> {
+        BitBoard::read_from(&written(board)[..]).unwrap()
+    }
+
+    #[test]
+    fn boards_round_trip() {
+        for seed in 1..10 {
+            let board = random_board::<U20, u16>(seed);
+            assert_eq!(round_trip(&board).to_blocks(), board.to_blocks());
+            let board = random_board::<U400, u64>(seed);
+            assert_eq!(round_trip(&board).to_blocks(), board.to_blocks());
+        }
+    }
+
+    // These pin the on-disk format. If one fails, the format has changed and needs a new
+    // FORMAT_VERSION rather than new golden files.
+    #[test]
+    fn golden_files_match() {
+        let glider = BitBoard::<U5, u32>::new(vec![(1, 4), (2, 3), (0, 2), (1, 2), (2, 2)]);
+        let diagonal = BitBoard::<U10, u8>::new((0..10).map(|i| (i, i)).collect());
+        let corners = BitBoard11x11::new(vec![(0, 0), (10, 0), (0, 10), (10, 10)]);
+
+        let golden = include_bytes!("golden/glider_5x5_u32.bin");
+        assert_eq!(
+            &golden[..],
+            &[b'B', b'B', 1, 4, 5, 0, 0, 0, 0x00, 0x1c, 0x22, 0x00]
+        );
+
+        assert_eq!(written(&glider), &golden[..]);
+        assert_eq!(
+            written(&diagonal),
+            &include_bytes!("golden/diagonal_10x10_u8.bin")[..]
+        );
+        assert_eq!(
+            written(&corners),
+            &include_bytes!("golden/corners_11x11_u128.bin")[..]
+        );
+
+        let read =
+            BitBoard::<U10, u8>::read_from(&include_bytes!("golden/diagonal_10x10_u8.bin")[..]);
+        assert_eq!(read.unwrap().to_blocks(), diagonal.to_blocks());
+        let read = BitBoard11x11::read_from(&include_bytes!("golden/corners_11x11_u128.bin")[..]);
+        assert_eq!(read.unwrap().as_raw(), corners.as_raw());
+    }
+
+    #[test]
+    fn header_is_checked() {
+        let golden = &include_bytes!("golden/glider_5x5_u32.bin")[..];
+        assert!(matches!(
+            BitBoard::<U6, u32>::read_from(golden),
+            Err(BinaryError::SizeMismatch {
+                expected: 6,
+                found: 5
+            })
+        ));
+        assert!(matches!(
+            BitBoard::<U5, u64>::read_from(golden),
+            Err(BinaryError::BlockMismatch {
+                expected: 8,
+                found: 4
+            })
+        ));
+
+        let mut bad = golden.to_vec();
+        bad[2] = FORMAT_VERSION + 1;
+        assert!(matches!(
+            BitBoard::<U5, u32>::read_from(&bad[..]),
+            Err(BinaryError::UnsupportedVersion(2))
+        ));
+        bad[0] = b'X';
+        assert!(matches!(
+            BitBoard::<U5, u32>::read_from(&bad[..]),
+            Err(BinaryError::BadMagic)
+        ));
+
+        let mut stray = golden.to_vec();
+        stray[11] = 0x80;
+        assert!(matches!(
+            BitBoard::<U5, u32>::read_from(&stray[..]),
+            Err(BinaryError::OutOfBounds)
+        ));
+        assert!(matches!(
+            BitBoard::<U5, u32>::read_from(&golden[..10]),
+            Err(BinaryError::Io(_))
+        ));
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::*;
+    use crate::internal::serialize::{deserialize_packed, serialize_packed};
+
+    #[test]
+    fn boards_are_readable_in_text_formats() {
+        let board = BitBoard3x3::new(vec![(0, 0), (2, 2)]);
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(json, r#"{"size":3,"rows":["..x","...","x.."]}"#);
+
+        let back: BitBoard3x3 = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.as_raw(), board.as_raw());
+
+        let cells: BitBoard3x3 =
+            serde_json::from_str(r#"{"size":3,"cells":[[0,0],[2,2]]}"#).unwrap();
+        assert_eq!(cells.as_raw(), board.as_raw());
+    }
+
+    #[test]
+    fn text_formats_are_validated() {
+        let err = |json| {
+            serde_json::from_str::<BitBoard3x3>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(err(r#"{"size":4,"rows":[]}"#).contains("expected a 3x3 board, found 4x4"));
+        assert!(err(r#"{"size":3,"rows":["...","..",".."]}"#).contains("line 2"));
+        assert!(err(r#"{"size":3,"cells":[[3,0]]}"#).contains("off the board"));
+        assert!(err(r#"{"size":3}"#).contains("one of rows or cells"));
+    }
+
+    #[test]
+    fn packed_form_round_trips() {
+        for seed in 1..10 {
+            let board = random_board::<U20, u32>(seed);
+            let mut json = Vec::new();
+            serialize_packed(&board, &mut serde_json::Serializer::new(&mut json)).unwrap();
+
+            let mut de = serde_json::Deserializer::from_slice(&json);
+            let back: BitBoard<U20, u32> = deserialize_packed(&mut de).unwrap();
+            assert_eq!(back.to_blocks(), board.to_blocks());
+
+            let mut de = serde_json::Deserializer::from_slice(&json);
+            assert!(deserialize_packed::<U21, u32, _>(&mut de).is_err());
+        }
+    }
+
+    // bincode isn't human readable, so it gets the packed bytes behind a length prefix
+    #[test]
+    fn binary_formats_get_the_packed_form() {
+        for seed in 1..10 {
+            let board = random_board::<U11, u128>(seed);
+            let bytes = bincode::serialize(&board).unwrap();
+
+            let mut packed = Vec::new();
+            board.write_to(&mut packed).unwrap();
+            assert_eq!(bytes[..8], (packed.len() as u64).to_le_bytes());
+            assert_eq!(bytes[8..], packed[..]);
+
+            let back: BitBoard11x11 = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(back.to_blocks(), board.to_blocks());
+            assert!(bincode::deserialize::<BitBoard<U12, u128>>(&bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn patterns_stay_normalised() {
+        let pattern: MovePattern =
+            serde_json::from_str(r#"[{"Up":1},{"UpLeft":[0,1]},{"Up":1}]"#).unwrap();
+        assert_eq!(pattern.moves(), &[Move::Left(1), Move::Up(1)]);
+        assert_eq!(
+            serde_json::to_string(&pattern).unwrap(),
+            r#"[{"Left":1},{"Up":1}]"#
+        );
+    }
+
+    #[test]
+    fn moves_round_trip() {
+        let moves = vec![Move::NullMove, Move::Up(2), Move::DownLeft(1, 3)];
+        let json = serde_json::to_string(&moves).unwrap();
+        assert_eq!(json, r#"["NullMove",{"Up":2},{"DownLeft":[1,3]}]"#);
+        assert_eq!(serde_json::from_str::<Vec<Move>>(&json).unwrap(), moves);
+
+        let json = serde_json::to_string(&Rotation::Clockwise).unwrap();
+        assert!(matches!(
+            serde_json::from_str(&json).unwrap(),
+            Rotation::Clockwise
+        ));
+    }
+}
+
+mod betza {
+    use super::*;
+
+    fn from(x: usize, y: usize, notation: &str) -> BitBoard8x8 {
+        let betza: Betza = notation.parse().unwrap();
+        betza.apply(&BitBoard8x8::new(vec![(x, y)]), None)
+    }
+
+    #[test]
+    fn offsets_round_trip() {
+        for &(x, y) in [
+            (0, 0),
+            (3, 0),
+            (-2, 0),
+            (0, 5),
+            (0, -1),
+            (1, 2),
+            (-3, 4),
+            (-1, -1),
+            (2, -7),
+        ]
+        .iter()
+        {
+            assert_eq!(Move::from_offset(x, y).offset(), (x, y));
+        }
+        assert_eq!(Move::from_offset(-1, 2), Move::UpLeft(2, 1));
+        assert_eq!(Move::from_offset(0, -2), Move::Down(2));
+    }
+
+    #[test]
+    fn leapers_work() {
+        assert_eq!(from(3, 3, "N").count_ones(), 8);
+        assert_eq!(from(0, 0, "N").count_ones(), 2);
+        assert_eq!(from(3, 3, "K").count_ones(), 8);
+
+        let wazir_ferz = from(3, 3, "WfF");
+        assert_eq!(wazir_ferz.count_ones(), 6);
+        assert!(wazir_ferz.is_set(2, 4) && wazir_ferz.is_set(4, 4));
+        assert!(!wazir_ferz.is_set(2, 2));
+
+        let king = BitBoard8x8::new(vec![(3, 3)]);
+        let expected = king
+            .moves()
+            .translate(Move::Up(1))
+            .translate(Move::UpRight(1, 1))
+            .rotate(Rotation::Clockwise)
+            .mirror()
+            .collect();
+        assert_eq!(from(3, 3, "K").as_raw(), expected.as_raw());
+    }
+
+    #[test]
+    fn directions_work() {
+        let wide = from(3, 3, "fsN");
+        assert_eq!(wide.count_ones(), 2);
+        assert!(wide.is_set(1, 4) && wide.is_set(5, 4));
+
+        let narrow = from(3, 3, "ffN");
+        assert_eq!(narrow.count_ones(), 2);
+        assert!(narrow.is_set(2, 5) && narrow.is_set(4, 5));
+
+        assert_eq!(
+            from(3, 3, "flF").as_raw(),
+            BitBoard8x8::new(vec![(2, 4)]).as_raw()
+        );
+        assert_eq!(from(3, 3, "fbW").count_ones(), 2);
+        assert_eq!(from(3, 3, "sW").count_ones(), 2);
+        assert_eq!(from(3, 3, "vR").count_ones(), 7);
+    }
+
+    #[test]
+    fn riders_work() {
+        let rook: Betza = "R4".parse().unwrap();
+        let board = BitBoard::<U10, u64>::new(vec![(0, 0)]);
+        assert_eq!(rook.apply(&board, None).count_ones(), 8);
+
+        assert_eq!(from(0, 0, "NN").count_ones(), 6);
+        assert_eq!(from(0, 0, "R").count_ones(), 14);
+        assert_eq!(from(3, 3, "Q").count_ones(), 27);
+        assert_eq!("Q".parse::<Betza>().unwrap().rays().len(), 8);
+        assert_eq!(
+            "WW".parse::<Betza>().unwrap(),
+            "R".parse::<Betza>().unwrap()
+        );
+    }
+
+    #[test]
+    fn riders_stop_at_blockers() {
+        let rook: Betza = "R".parse().unwrap();
+        let knight: Betza = "N".parse().unwrap();
+        let from = BitBoard8x8::new(vec![(0, 0)]);
+        let blockers = BitBoard8x8::new(vec![(0, 3), (1, 1), (1, 0)]);
+
+        let reach = rook.apply(&from, Some(&blockers));
+        assert_eq!(reach.count_ones(), 4);
+        assert!(reach.is_set(0, 3) && reach.is_set(1, 0));
+        assert!(!reach.is_set(0, 4));
+
+        assert_eq!(knight.apply(&from, Some(&blockers)).count_ones(), 2);
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        assert_eq!(
+            "WxF".parse::<Betza>().unwrap_err(),
+            BetzaError {
+                position: 1,
+                found: Some('x')
+            }
+        );
+        assert_eq!(
+            "Nf".parse::<Betza>().unwrap_err(),
+            BetzaError {
+                position: 2,
+                found: None
+            }
+        );
+        assert_eq!(
+            "Nf".parse::<Betza>().unwrap_err().to_string(),
+            "expected an atom at position 2"
+        );
+    }
+}
+
+mod pattern {
+    use super::*;
+    use std::sync::LazyLock;
+
+    static KNIGHT: LazyLock<MovePattern> = LazyLock::new(|| {
+        MovePattern::new(vec![Move::UpRight(2, 1), Move::UpRight(1, 2)])
+            .rotate(Rotation::Clockwise)
+            .mirror()
+    });
+
+    #[test]
+    fn patterns_are_normalised() {
+        let pattern = MovePattern::new(vec![
+            Move::Up(1),
+            Move::UpLeft(1, 0),
+            Move::Up(1),
+            Move::DownRight(0, 2),
+        ]);
+        assert_eq!(pattern.moves(), &[Move::Right(2), Move::Up(1)]);
+        assert_eq!(MovePattern::new(vec![Move::Right(2), Move::Up(1)]), pattern);
+        assert_eq!(KNIGHT.len(), 8);
+    }
+
+    #[test]
+    fn patterns_apply_to_any_size() {
+        let small = KNIGHT.apply(&BitBoard8x8::new(vec![(0, 0)]));
+        assert_eq!(small.count_ones(), 2);
+
+        let large = KNIGHT.apply(&BitBoard::<U19, u32>::new(vec![(9, 9)]));
+        assert_eq!(large.count_ones(), 8);
+        assert!(large.is_set(10, 11) && large.is_set(7, 8));
+
+        let betza: Betza = "N".parse().unwrap();
+        assert_eq!(betza.to_pattern(8), *KNIGHT);
+    }
+
+    #[test]
+    fn patterns_combine() {
+        let wazir: MovePattern = vec![Move::Up(1)].into();
+        let wazir = wazir.rotate(Rotation::Clockwise).mirror();
+        let ferz = MovePattern::new(vec![Move::UpRight(1, 1)])
+            .rotate(Rotation::Clockwise)
+            .mirror();
+
+        let king = &wazir | &ferz;
+        assert_eq!(king.len(), 8);
+        assert_eq!(king, "K".parse::<Betza>().unwrap().to_pattern(8));
+        assert_eq!(
+            wazir.clone().repeat(7),
+            "R".parse::<Betza>().unwrap().to_pattern(7)
+        );
+
+        let board = BitBoard8x8::new(vec![(3, 3)]);
+        let built = board.moves().pattern(&king).collect();
+        assert_eq!(built.as_raw(), king.apply(&board).as_raw());
+    }
+}
+
+mod attack {
+    use super::*;
+
+    fn knight() -> MovePattern {
+        "N".parse::<Betza>().unwrap().to_pattern(1)
+    }
+
+    #[test]
+    fn table_matches_pattern() {
+        let pattern = knight();
+        let table = AttackTable::<U8, u64>::new(&pattern);
+        for y in 0..8 {
+            for x in 0..8 {
+                let expected = pattern.apply(&BitBoard8x8::new(vec![(x, y)]));
+                assert_eq!(table.attacks(x, y).as_raw(), expected.as_raw());
+            }
+        }
+        assert_eq!(table.attacks(0, 0).count_ones(), 2);
+        assert_eq!(table.attacks(4, 4).count_ones(), 8);
+    }
+
+    #[test]
+    fn union_matches_pattern() {
+        let pattern = knight();
+        let table = AttackTable::<U13, u16>::new(&pattern);
+        for seed in 1..10 {
+            let from = random_board::<U13, u16>(seed);
+            let expected = pattern.apply(&from);
+            assert_eq!(table.attacks_from(&from).to_blocks(), expected.to_blocks());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn off_board_lookup_panics() {
+        AttackTable::<U8, u64>::new(&knight()).attacks(8, 0);
+    }
+}
+
+mod generate {
+    use super::*;
+
+    fn knight_moves(board: &BitBoard8x8) -> Moves<'_, U8, u64> {
+        board
+            .moves()
+            .translate(Move::UpRight(2, 1))
+            .translate(Move::UpRight(1, 2))
+            .rotate(Rotation::Clockwise)
+            .mirror()
+    }
+
+    #[test]
+    fn ones_match_is_set() {
+        for seed in 1..10 {
+            let board = random_board::<U11, u128>(seed);
+            let expected: Vec<(usize, usize)> = (0..11)
+                .flat_map(|y| (0..11).map(move |x| (x, y)))
+                .filter(|&(x, y)| board.is_set(x, y))
+                .collect();
+            assert_eq!(board.ones().collect::<Vec<_>>(), expected);
+        }
+        assert_eq!(BitBoard::<U20, u8>::default().ones().count(), 0);
+    }
+
+    #[test]
+    fn destinations_are_per_source() {
+        let knights = BitBoard8x8::new(vec![(1, 0), (6, 0)]);
+        let by_source: Vec<_> = knight_moves(&knights).by_source().destinations().collect();
+
+        assert_eq!(by_source.len(), 2);
+        assert_eq!(by_source[0].0, (1, 0));
+        assert_eq!(
+            by_source[0].1.ones().collect::<Vec<_>>(),
+            vec![(3, 1), (0, 2), (2, 2)]
+        );
+        assert_eq!(by_source[1].0, (6, 0));
+        assert_eq!(by_source[1].1.count_ones(), 3);
+
+        let union = knight_moves(&knights).collect();
+        let pairs: Vec<_> = knight_moves(&knights).by_source().pairs().collect();
+        assert_eq!(pairs.len(), 6);
+        assert!(pairs.iter().all(|&(_, (x, y))| union.is_set(x, y)));
+    }
+
+    #[test]
+    fn masks_filter_destinations() {
+        let knights = BitBoard8x8::new(vec![(1, 0)]);
+        let friendly = BitBoard8x8::new(vec![(3, 1), (1, 0)]);
+        let enemy = BitBoard8x8::new(vec![(2, 2), (5, 5)]);
+
+        let quiet: Vec<_> = knight_moves(&knights)
+            .by_source()
+            .friendly(&friendly)
+            .pairs()
+            .collect();
+        assert_eq!(quiet, vec![((1, 0), (0, 2)), ((1, 0), (2, 2))]);
+
+        let captures: Vec<_> = knight_moves(&knights)
+            .by_source()
+            .friendly(&friendly)
+            .enemy(&enemy)
+            .pairs()
+            .collect();
+        assert_eq!(captures, vec![((1, 0), (2, 2))]);
+    }
+}
+
+mod moves_iter {
+    use super::*;
+
+    #[test]
+    fn yields_each_move_with_its_board() {
+        let board = random_board::<U12, u32>(3);
+        let moves = || {
+            board
+                .moves()
+                .translate(Move::UpRight(1, 2))
+                .repeat(3)
+                .rotate(Rotation::Clockwise)
+        };
+
+        let expected = moves().collect();
+        let mut union = BitBoard::<U12, u32>::default();
+        let mut count = 0;
+        for (m, shifted) in moves() {
+            assert_eq!(shifted.to_string(), (&board << m).to_string());
+            union |= &shifted;
+            count += 1;
+        }
+        assert_eq!(count, moves().into_iter().size_hint().0);
+        assert_eq!(union.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn stops_at_first_hit() {
+        let board = BitBoard8x8::new(vec![(0, 0)]);
+        let hit = board
+            .moves()
+            .translate(Move::Up(1))
+            .repeat(7)
+            .into_iter()
+            .find(|(_, b)| b.is_set(0, 3));
+        assert_eq!(hit.map(|(m, _)| m), Some(Move::Up(3)));
+    }
+
+    #[test]
+    fn live_moves_keep_a_cell() {
+        let board = BitBoard8x8::new(vec![(6, 1)]);
+        let moves = board
+            .moves()
+            .translate(Move::Right(1))
+            .translate(Move::Right(2))
+            .translate(Move::DownLeft(2, 1))
+            .translate(Move::UpLeft(6, 6));
+        assert_eq!(
+            moves.live().collect::<Vec<_>>(),
+            vec![Move::Right(1), Move::UpLeft(6, 6)]
+        );
+
+        let empty = BitBoard8x8::default();
+        assert_eq!(empty.moves().translate(Move::Up(1)).live().count(), 0);
+
+        for seed in 1..5 {
+            let board = random_board::<U11, u128>(seed);
+            let moves = board
+                .moves()
+                .translate(Move::UpRight(3, 4))
+                .repeat(3)
+                .rotate(Rotation::Clockwise);
+            let live: Vec<_> = moves.live().collect();
+            let expected: Vec<_> = moves
+                .into_iter()
+                .filter(|(_, b)| b.count_ones() > 0)
+                .map(|(m, _)| m)
+                .collect();
+            assert_eq!(live, expected);
+        }
+    }
+}
+
+mod directions {
+    use super::*;
+
+    fn moves_of(moves: Moves<'_, U8, u64>) -> Vec<Move> {
+        moves.into_iter().map(|(m, _)| m).collect()
+    }
+
+    fn cells(board: &BitBoard8x8) -> Vec<(usize, usize)> {
+        board.ones().collect()
+    }
+
+    #[test]
+    fn steps_collapse() {
+        let bb = BitBoard8x8::new(vec![(3, 3)]);
+        assert_eq!(moves_of(bb.moves().left(1).up(1)), vec![Move::UpLeft(1, 1)]);
+        assert_eq!(moves_of(bb.moves().up(1).up(2)), vec![Move::Up(3)]);
+        assert_eq!(
+            moves_of(bb.moves().down(2).right(1).down(1)),
+            vec![Move::DownRight(2, 1), Move::Down(1)]
+        );
+        assert_eq!(
+            moves_of(bb.moves().down(2).right(1).right(1)),
+            vec![Move::DownRight(2, 2)]
+        );
+        assert_eq!(
+            moves_of(bb.moves().up(1).left(1).left(1)),
+            vec![Move::UpLeft(1, 2)]
+        );
+        assert_eq!(
+            moves_of(bb.moves().up(1).left(1).right(1)),
+            vec![Move::UpLeft(1, 1), Move::Right(1)]
+        );
+
+        let dynamic = DynBitBoard::from(&bb);
+        let reached = dynamic.moves().up(1).left(1).left(1).collect();
+        assert!(reached.is_set(1, 4) && reached.count_ones() == 1);
+        assert_eq!(
+            moves_of(bb.moves().left(1).right(1)),
+            vec![Move::Left(1), Move::Right(1)]
+        );
+        assert_eq!(
+            moves_of(bb.moves().left(1).mirror()),
+            vec![Move::Left(1), Move::Right(1)]
+        );
+        assert_eq!(
+            moves_of(bb.moves().translate(Move::Left(1)).up(1)),
+            vec![Move::Left(1), Move::Up(1)]
+        );
+    }
+
+    #[test]
+    fn zero_length_steps_collapse_away() {
+        let bb = BitBoard8x8::new(vec![(3, 3)]);
+        assert_eq!(moves_of(bb.moves().left(1).up(0)), vec![Move::Left(1)]);
+        assert_eq!(moves_of(bb.moves().right(1).down(0)), vec![Move::Right(1)]);
+        assert_eq!(moves_of(bb.moves().up(0).left(2)), vec![Move::Left(2)]);
+
+        assert_eq!(cells(&bb.moves().left(1).up(0).collect()), vec![(2, 3)]);
+        assert_eq!(cells(&bb.moves().right(1).down(0).collect()), vec![(4, 3)]);
+
+        let dynamic = DynBitBoard::from(&bb);
+        let left = dynamic.moves().left(1).up(0).collect();
+        let right = dynamic.moves().right(1).down(0).collect();
+        assert!(left.is_set(2, 3) && left.count_ones() == 1);
+        assert!(right.is_set(4, 3) && right.count_ones() == 1);
+    }
+
+    #[test]
+    fn repeat_makes_a_ray() {
+        let bb = BitBoard8x8::new(vec![(7, 0)]);
+        let ray = bb.moves().left(1).repeat(8).collect();
+        assert_eq!(cells(&ray), (0..7).map(|x| (x, 0)).collect::<Vec<_>>());
+
+        assert_eq!(
+            moves_of(bb.moves().left(1).repeat(3)),
+            vec![Move::Left(1), Move::Left(2), Move::Left(3)]
+        );
+        assert_eq!(
+            moves_of(bb.moves().translate(Move::Up(1)).left(1).repeat(2)),
+            vec![Move::Up(1), Move::Left(1), Move::Left(2)]
+        );
+
+        // A step after a repeat starts a new move rather than bending the ray
+        let bent = bb.moves().left(1).repeat(8).up(1).collect();
+        assert_eq!(bent.count_ones(), 8);
+        assert!(bent.is_set(7, 1));
+
+        let both = BitBoard8x8::new(vec![(3, 0)])
+            .moves()
+            .left(1)
+            .repeat(8)
+            .mirror()
+            .collect();
+        assert_eq!(
+            cells(&both),
+            vec![(0, 0), (1, 0), (2, 0), (4, 0), (5, 0), (6, 0), (7, 0)]
+        );
+    }
+
+    // The examples from NOTES.md and shifting.md
+    #[test]
+    fn castle() {
+        let bb = BitBoard8x8::new(vec![(3, 3)]);
+        let castle = || {
+            bb.moves()
+                .left(1)
+                .repeat(8)
+                .right(1)
+                .repeat(8)
+                .up(1)
+                .repeat(8)
+                .down(1)
+                .repeat(8)
+        };
+        assert_eq!(castle().into_iter().count(), 32);
+        let castle_moves = castle().collect();
+
+        let mut expected = BitBoard8x8::default();
+        (0..8).filter(|&i| i != 3).for_each(|i| {
+            expected.set(i, 3);
+            expected.set(3, i);
+        });
+        assert_eq!(castle_moves.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn knight() {
+        let bb = BitBoard8x8::new(vec![(3, 3)]);
+        let knight_moves = bb
+            .moves()
+            .up(1)
+            .left(2)
+            .up(1)
+            .right(2)
+            .up(2)
+            .left(1)
+            .up(2)
+            .right(1)
+            .mirror()
+            .collect();
+
+        let expected = BitBoard8x8::new(vec![
+            (2, 1),
+            (4, 1),
+            (1, 2),
+            (5, 2),
+            (1, 4),
+            (5, 4),
+            (2, 5),
+            (4, 5),
+        ]);
+        assert_eq!(knight_moves.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn dynamic_boards_match() {
+        let bb = BitBoard8x8::new(vec![(3, 3)]);
+        let dynamic = DynBitBoard::from(&bb);
+        let fixed = bb
+            .moves()
+            .up(2)
+            .left(1)
+            .down(1)
+            .repeat(3)
+            .mirror()
+            .collect();
+        let dyn_moves = dynamic
+            .moves()
+            .up(2)
+            .left(1)
+            .down(1)
+            .repeat(3)
+            .mirror()
+            .collect();
+        assert_eq!(dyn_moves.to_string(), fixed.to_string());
+    }
+}
+
+mod symmetry {
+    use super::*;
+
+    fn offsets(moves: Moves<'_, U8, u64>) -> Vec<(isize, isize)> {
+        let mut offsets: Vec<_> = moves.into_iter().map(|(m, _)| m.offset()).collect();
+        offsets.sort();
+        offsets
+    }
+
+    #[test]
+    fn rotations_turn_the_offset() {
+        assert_eq!(
+            Move::UpLeft(1, 2).rotate(Rotation::Clockwise),
+            Move::UpRight(2, 1)
+        );
+        assert_eq!(
+            Move::UpLeft(1, 2).rotate(Rotation::AntiClockwise),
+            Move::DownLeft(2, 1)
+        );
+        assert_eq!(Move::Up(3).rotate(Rotation::HalfTurn), Move::Down(3));
+
+        let all = [
+            Move::Up(2),
+            Move::Left(1),
+            Move::UpLeft(1, 3),
+            Move::DownRight(2, 5),
+            Move::NullMove,
+        ];
+        for &m in all.iter() {
+            assert_eq!(m.rotate(Rotation::HalfTurn), m.mirror());
+            let quarter = m.rotate(Rotation::Clockwise);
+            assert_eq!(quarter.rotate(Rotation::AntiClockwise), m);
+            assert_eq!(
+                quarter.rotate(Rotation::Clockwise),
+                m.rotate(Rotation::HalfTurn)
+            );
+        }
+    }
+
+    #[test]
+    fn reflections_flip_the_offset() {
+        let m = Move::UpRight(2, 1);
+        assert_eq!(m.reflect(Reflection::Vertical), Move::UpLeft(2, 1));
+        assert_eq!(m.reflect(Reflection::Horizontal), Move::DownRight(2, 1));
+        assert_eq!(m.reflect(Reflection::Diagonal), Move::UpRight(1, 2));
+        assert_eq!(m.reflect(Reflection::AntiDiagonal), Move::DownLeft(1, 2));
+        assert_eq!(Move::Up(1).reflect(Reflection::Diagonal), Move::Right(1));
+        assert_eq!(Move::Left(4).reflect(Reflection::Vertical), Move::Right(4));
+    }
+
+    #[test]
+    fn builder_reflects() {
+        let bb = BitBoard8x8::new(vec![(3, 3)]);
+        assert_eq!(
+            offsets(bb.moves().up(2).right(1).reflect(Reflection::Vertical)),
+            vec![(-1, 2), (1, 2)]
+        );
+        assert_eq!(
+            offsets(bb.moves().up(1).rotate(Rotation::HalfTurn)),
+            vec![(0, -1), (0, 1)]
+        );
+    }
+
+    #[test]
+    fn gold_is_left_right_symmetric() {
+        let bb = BitBoard8x8::new(vec![(3, 3)]);
+        let gold = bb
+            .moves()
+            .translate(Move::Up(1))
+            .translate(Move::UpRight(1, 1))
+            .translate(Move::Right(1))
+            .translate(Move::Down(1))
+            .symmetrize(Symmetry::Vertical);
+        assert_eq!(
+            offsets(gold),
+            vec![(-1, 0), (-1, 1), (0, -1), (0, 1), (1, 0), (1, 1)]
+        );
+
+        // Already symmetric, so nothing is added
+        assert_eq!(
+            offsets(bb.moves().up(1).symmetrize(Symmetry::Vertical)),
+            vec![(0, 1)]
+        );
+    }
+
+    #[test]
+    fn groups_have_the_right_orbits() {
+        let knight = MovePattern::new(vec![Move::UpRight(2, 1)]);
+        let orbit = |group| knight.clone().symmetrize(group).len();
+        assert_eq!(orbit(Symmetry::HalfTurn), 2);
+        assert_eq!(orbit(Symmetry::Rotational), 4);
+        assert_eq!(orbit(Symmetry::Vertical), 2);
+        assert_eq!(orbit(Symmetry::Diagonal), 2);
+        assert_eq!(orbit(Symmetry::Orthogonal), 4);
+        assert_eq!(orbit(Symmetry::Full), 8);
+        assert_eq!(
+            knight.symmetrize(Symmetry::Full),
+            "N".parse::<Betza>().unwrap().to_pattern(1)
+        );
+
+        let ferz = MovePattern::new(vec![Move::UpRight(1, 1)]);
+        assert_eq!(ferz.clone().symmetrize(Symmetry::Diagonal).len(), 1);
+        assert_eq!(ferz.symmetrize(Symmetry::AntiDiagonal).len(), 2);
+    }
+
+    #[test]
+    fn dynamic_boards_match() {
+        let bb = BitBoard8x8::new(vec![(2, 5)]);
+        let dynamic = DynBitBoard::from(&bb);
+        let fixed = bb
+            .moves()
+            .up(2)
+            .left(1)
+            .reflect(Reflection::AntiDiagonal)
+            .symmetrize(Symmetry::Orthogonal)
+            .collect();
+        let dyn_moves = dynamic
+            .moves()
+            .up(2)
+            .left(1)
+            .reflect(Reflection::AntiDiagonal)
+            .symmetrize(Symmetry::Orthogonal)
+            .collect();
+        assert_eq!(dyn_moves.to_string(), fixed.to_string());
+        assert_eq!(fixed.count_ones(), 8);
+    }
+}
+
+mod masks {
+    use super::*;
+
+    fn rank(y: usize) -> BitBoard8x8 {
+        BitBoard8x8::new((0..8).map(|x| (x, y)).collect())
+    }
+
+    fn cells(board: &BitBoard8x8) -> Vec<(usize, usize)> {
+        board.ones().collect()
+    }
+
+    #[test]
+    fn double_push_from_second_rank() {
+        let pawns = BitBoard8x8::new(vec![(0, 1), (3, 4)]);
+        let pushes = pawns
+            .moves()
+            .up(1)
+            .translate(Move::Up(2))
+            .from_mask(rank(1))
+            .collect();
+        assert_eq!(cells(&pushes), vec![(0, 2), (0, 3), (3, 5)]);
+    }
+
+    #[test]
+    fn promotion_on_last_rank() {
+        let pawns = BitBoard8x8::new(vec![(2, 6), (5, 3)]);
+        let promotions = pawns.moves().up(1).to_mask(rank(7)).collect();
+        assert_eq!(cells(&promotions), vec![(2, 7)]);
+
+        // Masks on the same move narrow each other
+        let none = pawns
+            .moves()
+            .up(1)
+            .to_mask(rank(7))
+            .to_mask(rank(4))
+            .collect();
+        assert_eq!(none.count_ones(), 0);
+    }
+
+    #[test]
+    fn masks_stay_with_their_move() {
+        let king = BitBoard8x8::new(vec![(4, 0)]);
+        let home = BitBoard8x8::new(vec![(4, 0)]);
+
+        let moved = BitBoard8x8::new(vec![(4, 1)]);
+        let castles = |board: &BitBoard8x8| {
+            board
+                .moves()
+                .up(1)
+                .translate(Move::Right(2))
+                .from_mask(home.clone())
+                .translate(Move::Left(2))
+                .from_mask(home.clone())
+                .collect()
+        };
+        assert_eq!(cells(&castles(&king)), vec![(2, 0), (6, 0), (4, 1)]);
+        assert_eq!(cells(&castles(&moved)), vec![(4, 2)]);
+
+        // Nothing to attach to
+        let unmasked = king.moves().from_mask(rank(3)).up(1).collect();
+        assert_eq!(cells(&unmasked), vec![(4, 1)]);
+    }
+
+    #[test]
+    fn masks_turn_with_their_move() {
+        let pushes = |pawns: &BitBoard8x8| {
+            pawns
+                .moves()
+                .up(1)
+                .translate(Move::Up(2))
+                .from_mask(rank(1))
+                .mirror()
+                .collect()
+        };
+        // Mirrored, the double push goes down from the seventh rank
+        let black = BitBoard8x8::new(vec![(0, 6)]);
+        assert_eq!(cells(&pushes(&black)), vec![(0, 4), (0, 5), (0, 7)]);
+        let white = BitBoard8x8::new(vec![(0, 1)]);
+        assert_eq!(cells(&pushes(&white)), vec![(0, 0), (0, 2), (0, 3)]);
+
+        // A quarter turn clockwise takes the second rank to the second file
+        let rooks = BitBoard8x8::new(vec![(1, 3), (6, 3)]);
+        let turned = rooks
+            .moves()
+            .translate(Move::Up(2))
+            .from_mask(rank(1))
+            .rotate(Rotation::Clockwise)
+            .collect();
+        assert_eq!(cells(&turned), vec![(3, 3)]);
+
+        // repeat leaves the mask where it is
+        let pawn = BitBoard8x8::new(vec![(0, 1)]);
+        let rays = pawn
+            .moves()
+            .translate(Move::Up(1))
+            .from_mask(rank(1))
+            .repeat(3)
+            .collect();
+        assert_eq!(cells(&rays), vec![(0, 2), (0, 3), (0, 4)]);
+    }
+
+    #[test]
+    fn iterators_respect_masks() {
+        let pawns = BitBoard8x8::new(vec![(3, 4)]);
+        let moves = || {
+            pawns
+                .moves()
+                .up(1)
+                .translate(Move::Up(2))
+                .from_mask(rank(1))
+        };
+
+        assert_eq!(moves().live().collect::<Vec<_>>(), vec![Move::Up(1)]);
+        let boards: Vec<_> = moves().into_iter().map(|(_, b)| b.count_ones()).collect();
+        assert_eq!(boards, vec![1, 0]);
+
+        let pairs: Vec<_> = moves().by_source().pairs().collect();
+        assert_eq!(pairs, vec![((3, 4), (3, 5))]);
+    }
+
+    #[test]
+    fn symmetrize_keeps_masked_moves_apart() {
+        let rook = BitBoard8x8::new(vec![(0, 0)]);
+        let moves = || {
+            rook.moves()
+                .translate(Move::Up(1))
+                .translate(Move::Right(1))
+                .to_mask(rank(5))
+                .symmetrize(Symmetry::Diagonal)
+        };
+        // Up(1) reflects onto an unmasked Right(1), which the masked one doesn't cover. The
+        // masked Right(1) reflects onto Up(1), which is already there without a mask.
+        assert_eq!(moves().into_iter().count(), 3);
+        assert_eq!(cells(&moves().collect()), vec![(1, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn dynamic_boards_take_masks() {
+        let pawns = BitBoard8x8::new(vec![(0, 1), (3, 4), (5, 6)]);
+        let fixed = pawns
+            .moves()
+            .up(1)
+            .translate(Move::Up(2))
+            .from_mask(rank(1))
+            .up(1)
+            .to_mask(rank(7))
+            .mirror()
+            .collect();
+
+        let dynamic = DynBitBoard::from(&pawns);
+        let dyn_rank = |y| DynBitBoard::from(rank(y));
+        let moved = dynamic
+            .moves()
+            .up(1)
+            .translate(Move::Up(2))
+            .from_mask(dyn_rank(1))
+            .up(1)
+            .to_mask(dyn_rank(7))
+            .mirror()
+            .collect();
+        assert_eq!(moved, DynBitBoard::from(&fixed));
+    }
+}
+
+mod notation {
+    use super::*;
+
+    #[test]
+    fn moves_round_trip() {
+        let moves = [
+            (Move::NullMove, "0"),
+            (Move::Up(1), "U1"),
+            (Move::Down(12), "D12"),
+            (Move::Left(3), "L3"),
+            (Move::Right(3), "R3"),
+            (Move::UpLeft(1, 2), "UL1,2"),
+            (Move::UpRight(2, 1), "UR2,1"),
+            (Move::DownLeft(3, 4), "DL3,4"),
+            (Move::DownRight(5, 6), "DR5,6"),
+        ];
+        for &(m, text) in moves.iter() {
+            assert_eq!(m.to_string(), text);
+            assert_eq!(text.parse::<Move>(), Ok(m));
+        }
+    }
+
+    #[test]
+    fn zero_components_are_normalised() {
+        assert_eq!("DL0,4".parse::<Move>(), Ok(Move::Left(4)));
+        assert_eq!("UL0,2".parse::<Move>(), Ok(Move::Left(2)));
+        assert_eq!("DR0,2".parse::<Move>(), Ok(Move::Right(2)));
+        assert_eq!("UR3,0".parse::<Move>(), Ok(Move::Up(3)));
+        assert_eq!("DR0,0".parse::<Move>(), Ok(Move::NullMove));
+
+        let bb = BitBoard8x8::new(vec![(3, 3)]);
+        let left = &bb << "UL0,2".parse::<Move>().unwrap();
+        let right = &bb << "DR0,2".parse::<Move>().unwrap();
+        assert_eq!(left.ones().collect::<Vec<_>>(), vec![(1, 3)]);
+        assert_eq!(right.ones().collect::<Vec<_>>(), vec![(5, 3)]);
+    }
+
+    #[test]
+    fn bad_moves_point_at_the_problem() {
+        let err = |s: &str| s.parse::<Move>().unwrap_err();
+        assert_eq!(
+            err(""),
+            NotationError {
+                position: 0,
+                found: None
+            }
+        );
+        assert_eq!(
+            err("X1"),
+            NotationError {
+                position: 0,
+                found: Some('X')
+            }
+        );
+        assert_eq!(
+            err("UL1"),
+            NotationError {
+                position: 3,
+                found: None
+            }
+        );
+        assert_eq!(
+            err("U1,2"),
+            NotationError {
+                position: 2,
+                found: Some(',')
+            }
+        );
+        assert_eq!(
+            err("LR1"),
+            NotationError {
+                position: 1,
+                found: Some('R')
+            }
+        );
+        assert_eq!(
+            err("R3*"),
+            NotationError {
+                position: 2,
+                found: Some('*')
+            }
+        );
+        assert!(err("U99999999999999999999999")
+            .to_string()
+            .contains("position"));
+    }
+
+    #[test]
+    fn lists_expand_rays() {
+        let rays: MovePattern = "R3*  UR2,4*\tD1".parse().unwrap();
+        let expected = MovePattern::new(vec![
+            Move::Right(1),
+            Move::Right(2),
+            Move::Right(3),
+            Move::UpRight(1, 2),
+            Move::UpRight(2, 4),
+            Move::Down(1),
+        ]);
+        assert_eq!(rays, expected);
+        assert_eq!(rays.to_string().parse::<MovePattern>(), Ok(expected));
+        assert_eq!("".parse::<MovePattern>(), Ok(MovePattern::default()));
+
+        let knight: MovePattern = "UR1,2 UR2,1 UL1,2 UL2,1 DR1,2 DR2,1 DL1,2 DL2,1"
+            .parse()
+            .unwrap();
+        assert_eq!(knight, "N".parse::<Betza>().unwrap().to_pattern(1));
+
+        assert_eq!(
+            "U1 R2x".parse::<MovePattern>(),
+            Err(NotationError {
+                position: 5,
+                found: Some('x')
+            })
+        );
+    }
+
+    #[test]
+    fn lists_keep_their_order() {
+        let moves = parse_moves("U1 L1 R3* U1").unwrap();
+        assert_eq!(
+            moves,
+            vec![
+                Move::Up(1),
+                Move::Left(1),
+                Move::Right(1),
+                Move::Right(2),
+                Move::Right(3),
+                Move::Up(1),
+            ]
+        );
+
+        // Patterns are sets, so the same text comes back sorted and deduplicated
+        let pattern: MovePattern = "U1 L1 R3* U1".parse().unwrap();
+        assert_eq!(pattern.to_string(), "L1 R1 R2 R3 U1");
+
+        assert_eq!(parse_moves(" "), Ok(vec![]));
+        assert_eq!(
+            parse_moves("U1 R2x"),
+            Err(NotationError {
+                position: 5,
+                found: Some('x')
+            })
+        );
+    }
+
+    #[test]
+    fn squares_are_algebraic() {
+        let squares = [
+            ((0, 0), "a1"),
+            ((4, 3), "e4"),
+            ((0, 9), "a10"),
+            ((25, 0), "z1"),
+            ((26, 1), "aa2"),
+            ((27, 99), "ab100"),
+        ];
+        for &(cell, text) in squares.iter() {
+            assert_eq!(Coord::from(cell).to_string(), text);
+            assert_eq!(text.parse::<Coord>().map(<(usize, usize)>::from), Ok(cell));
+        }
+
+        let err = |s: &str| s.parse::<Coord>().unwrap_err();
+        assert_eq!(
+            err("4e"),
+            NotationError {
+                position: 0,
+                found: Some('4')
+            }
+        );
+        assert_eq!(
+            err("e0"),
+            NotationError {
+                position: 1,
+                found: Some('0')
+            }
+        );
+        assert_eq!(
+            err("e"),
+            NotationError {
+                position: 1,
+                found: None
+            }
+        );
+        assert_eq!(
+            err("E4"),
+            NotationError {
+                position: 0,
+                found: Some('E')
+            }
+        );
+        assert_eq!(
+            err("e4 "),
+            NotationError {
+                position: 2,
+                found: Some(' ')
+            }
+        );
+
+        let board = BitBoard8x8::new(vec!["e4".parse::<Coord>().unwrap().into()]);
+        assert!(board.is_set(4, 3));
+    }
+}
+
+mod const_board {
+    use super::*;
+
+    type Go = ConstBitBoard<19, 19, { required_blocks_for::<u64>(19, 19) }>;
+    type Wide = ConstBitBoard<10, 4, { required_blocks_for::<u8>(10, 4) }, u8>;
+
+    #[test]
+    fn block_counts_work() {
+        assert_eq!(required_blocks_for::<u64>(19, 19), 6);
+        assert_eq!(required_blocks_for::<u8>(10, 4), 5);
+        assert_eq!(required_blocks_for::<u64>(8, 8), 1);
+        assert_eq!(required_blocks_for::<u128>(11, 11), 1);
+    }
+
+    #[test]
+    fn converts_to_and_from_typed() {
+        for seed in 1..20 {
+            let typed = random_board::<U19, u64>(seed);
+            let inline = Go::from(&typed);
+            assert_eq!(inline.count_ones(), typed.count_ones());
+            let back = BitBoard::<U19, u64>::from(inline);
+            assert_eq!(format!("{}", back), format!("{}", typed));
+        }
+    }
+
+    #[test]
+    fn matches_typed_board() {
+        let moves = [
+            Move::Up(3),
+            Move::Down(20),
+            Move::Left(7),
+            Move::Right(1),
+            Move::UpLeft(2, 5),
+            Move::DownRight(4, 4),
+        ];
+        for seed in 1..10 {
+            let a = random_board::<U8, u64>(seed);
+            let b = random_board::<U8, u64>(seed + 100);
+            let (ca, cb) = (ConstBitBoard8x8::from(&a), ConstBitBoard8x8::from(&b));
+
+            for &m in moves.iter() {
+                assert_eq!(ConstBitBoard8x8::from(&a << m), ca << m);
+            }
+            assert_eq!(ConstBitBoard8x8::from(&a & &b), ca & cb);
+            assert_eq!(ConstBitBoard8x8::from(&a | &b), ca | cb);
+            assert_eq!(ConstBitBoard8x8::from(&a ^ &b), ca ^ cb);
+            assert_eq!(ConstBitBoard8x8::from(!&a), !ca);
+            assert_eq!(format!("{}", a), format!("{}", ca));
+        }
+    }
+
+    #[test]
+    fn non_square_boards_work() {
+        let mut board = Wide::new(vec![(0, 0), (9, 3), (10, 0), (0, 4)]);
+        assert_eq!(board.count_ones(), 2);
+
+        board <<= Move::Right(1);
+        assert!(board.is_set(1, 0));
+        assert_eq!(board.count_ones(), 1);
+
+        board <<= Move::UpRight(3, 8);
+        assert!(board.is_set(9, 3));
+        board <<= Move::Up(1);
+        assert_eq!(board.count_ones(), 0);
+
+        assert_eq!((!Wide::default()).count_ones(), 40);
+        assert_eq!(
+            format!("{}", Wide::new(vec![(0, 3), (9, 0)])),
+            "1 0 0 0 0 0 0 0 0 0 \n\
+             0 0 0 0 0 0 0 0 0 0 \n\
+             0 0 0 0 0 0 0 0 0 0 \n\
+             0 0 0 0 0 0 0 0 0 1 \n"
+        );
+    }
+}
+
+mod cube {
+    use super::*;
+
+    type Qubic = BitBoard3D<U4, u16>;
+
+    #[test]
+    fn set_works() {
+        let mut bb = Qubic::new(vec![(0, 1, 2), (3, 3, 3)]);
+        assert!(bb.is_set(0, 1, 2));
+        assert!(!bb.is_set(0, 1, 1));
+        assert_eq!(bb.count_ones(), 2);
+
+        bb.unset(3, 3, 3);
+        bb.set(4, 0, 0);
+        assert_eq!(bb.count_ones(), 1);
+        assert!(bb.plane(2).is_set(0, 1));
+    }
+
+    #[test]
+    fn depth_moves_work() {
+        let bb = Qubic::new(vec![(1, 1, 0)]);
+
+        let forward = &bb << Move3D::new(Move::UpRight(1, 2), Depth::Forward(3));
+        assert!(forward.is_set(3, 2, 3));
+        assert_eq!(forward.count_ones(), 1);
+
+        let back = &forward << Move3D::new(Move::NullMove, Depth::Back(2));
+        assert!(back.is_set(3, 2, 1));
+
+        assert_eq!(
+            (&bb << Move3D::new(Move::NullMove, Depth::Back(1))).count_ones(),
+            0
+        );
+        assert_eq!(
+            (&bb << Move3D::new(Move::NullMove, Depth::Forward(4))).count_ones(),
+            0
+        );
+    }
+
+    #[test]
+    fn directions_reach_every_neighbour() {
+        let directions = Move3D::directions();
+        assert_eq!(directions.len(), 26);
+
+        let centre = BitBoard3D::<U3, u32>::new(vec![(1, 1, 1)]);
+        let mut neighbours = BitBoard3D::default();
+        for m in directions.iter() {
+            neighbours |= &(&centre << *m);
+            assert_eq!((&centre << *m << m.mirror()).count_ones(), 1);
+        }
+
+        assert_eq!(neighbours.count_ones(), 26);
+        assert!(!neighbours.is_set(1, 1, 1));
+    }
+
+    #[test]
+    fn lines_work() {
+        assert_eq!(Qubic::lines(4).len(), 76);
+        assert_eq!(BitBoard3D::<U3, u16>::lines(3).len(), 49);
+        assert!(Qubic::lines(5).is_empty());
+        assert!(Qubic::lines(4).iter().all(|line| line.count_ones() == 4));
+    }
+
+    #[test]
+    fn lines_detect_wins() {
+        let mut bb = Qubic::default();
+        (0..4).for_each(|i| bb.set(i, 3 - i, i));
+        let lines = Qubic::lines(4);
+        let win = |bb: &Qubic| lines.iter().any(|l| (l & bb).count_ones() == 4);
+
+        assert!(win(&bb));
+        bb.unset(2, 1, 2);
+        assert!(!win(&bb));
+    }
+}
+
+mod hex {
+    use super::*;
+
+    type Hex = HexBoard<U5, u32>;
+
+    #[test]
+    fn directions_match_offsets() {
+        for d in HexDirection::ALL.iter() {
+            let (dq, dr) = d.offset();
+            let moved = &Hex::new(vec![(2, 2)]) << *d;
+            assert!(
+                moved.is_set((2 + dq) as usize, (2 + dr) as usize),
+                "{:?}",
+                d
+            );
+            assert!((&moved << d.opposite()).is_set(2, 2));
+        }
+    }
+
+    #[test]
+    fn neighbours_work() {
+        let centre = Hex::new(vec![(2, 2)]).neighbours();
+        assert_eq!(centre.count_ones(), 6);
+        assert!(!centre.is_set(2, 2));
+        assert!(centre.is_set(1, 3) && centre.is_set(3, 1));
+        assert!(!centre.is_set(3, 3) && !centre.is_set(1, 1));
+
+        // Acute and obtuse corners of the rhombus
+        assert_eq!(Hex::new(vec![(0, 0)]).neighbours().count_ones(), 2);
+        assert_eq!(Hex::new(vec![(4, 0)]).neighbours().count_ones(), 3);
+    }
+
+    #[test]
+    fn flood_fill_works() {
+        // A chain from the bottom edge to the top edge, joined along the NorthWest diagonal
+        let stones = Hex::new(vec![(4, 0), (3, 1), (3, 2), (2, 3), (2, 4), (0, 0)]);
+        let bottom = Hex::new(vec![(4, 0)]);
+
+        let chain = bottom.flood_fill(&stones);
+        assert_eq!(chain.count_ones(), 5);
+        assert!(chain.is_set(2, 4));
+        assert!(!chain.is_set(0, 0));
+
+        let mut broken = stones.clone();
+        broken.unset(3, 2);
+        assert_eq!(bottom.flood_fill(&broken).count_ones(), 2);
+    }
+
+    #[test]
+    fn display_works() {
+        let bb = HexBoard::<U3, u16>::new(vec![(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(format!("{}", bb), "  0 0 1 \n 0 1 0 \n1 0 0 \n");
+    }
+}
+
+#[cfg(feature = "parallel")]
+mod parallel {
+    use super::*;
+
+    type Life = BitBoard<U1000, u64>;
+
+    const _: () = assert!(Life::PARALLEL && !BitBoard8x8::PARALLEL);
+
+    #[test]
+    fn collect_matches_sequential_union() {
+        use Move::*;
+        let bb = random_board::<U1000, u64>(42);
+        let neighbours = [
+            Up(1),
+            Down(1),
+            Left(1),
+            Right(1),
+            UpLeft(1, 1),
+            UpRight(1, 1),
+            DownLeft(1, 1),
+            DownRight(1, 1),
+        ];
+
+        let mut expected = Life::default();
+        for m in neighbours.iter() {
+            expected |= &bb << *m;
+        }
+
+        let mut moves = bb.moves();
+        for m in neighbours.iter() {
+            moves = moves.translate(*m);
+        }
+        let collected = moves.collect();
+
+        assert_eq!(collected.count_ones(), expected.count_ones());
+        assert_eq!((&collected ^ &expected).count_ones(), 0);
+    }
+    #[test]
+    fn dynamic_collect_matches_typed() {
+        let bb = random_board::<U1000, u64>(7);
+        let dynamic = DynBitBoard::from(&bb);
+        let typed = bb
+            .moves()
+            .up(1)
+            .right(1)
+            .rotate(Rotation::Clockwise)
+            .mirror();
+        let dyn_moves = dynamic
+            .moves()
+            .up(1)
+            .right(1)
+            .rotate(Rotation::Clockwise)
+            .mirror();
+        let (typed, dyn_moves) = (typed.collect(), dyn_moves.collect());
+        assert_eq!(typed.count_ones(), dyn_moves.count_ones());
+        assert_eq!((&DynBitBoard::from(&typed) ^ &dyn_moves).count_ones(), 0);
+    }
+}
+
+#[cfg(feature = "simd")]
+mod simd {
+    use super::xorshift;
+    use crate::internal::simd::{self, scalar};
+
+    fn random_bytes(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed | 1;
+        (0..len).map(|_| xorshift(&mut state) as u8).collect()
+    }
+
+    // Lengths either side of the 16 and 32 byte register widths
+    const LENGTHS: [usize; 10] = [0, 1, 7, 15, 16, 17, 31, 32, 33, 1250];
+
+    #[test]
+    fn bitwise_kernels_match_scalar() {
+        type Kernel = fn(&mut [u8], &[u8]);
+        let kernels: [(Kernel, Kernel); 3] = [
+            (simd::and_assign, scalar::and_assign),
+            (simd::or_assign, scalar::or_assign),
+            (simd::xor_assign, scalar::xor_assign),
+        ];
+
+        for &len in LENGTHS.iter() {
+            let src = random_bytes(len, 7);
+            for (vector, reference) in kernels.iter() {
+                let mut expected = random_bytes(len, 3);
+                let mut actual = expected.clone();
+                reference(&mut expected, &src);
+                vector(&mut actual, &src);
+                assert_eq!(actual, expected, "length {}", len);
+            }
+        }
+    }
+
+    // The dispatcher always picks AVX2 when it's available, so check SSE2 on its own
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn sse2_kernels_match_scalar() {
+        type Kernel = unsafe fn(&mut [u8], &[u8]);
+        type Reference = fn(&mut [u8], &[u8]);
+        let kernels: [(Kernel, Reference); 3] = [
+            (simd::sse2::and_assign, scalar::and_assign),
+            (simd::sse2::or_assign, scalar::or_assign),
+            (simd::sse2::xor_assign, scalar::xor_assign),
+        ];
+
+        for &len in LENGTHS.iter() {
+            let src = random_bytes(len, 5);
+            for (vector, reference) in kernels.iter() {
+                let mut expected = random_bytes(len, 9);
+                let mut actual = expected.clone();
+                reference(&mut expected, &src);
+                unsafe { vector(&mut actual, &src) };
+                assert_eq!(actual, expected, "length {}", len);
+            }
+        }
+    }
+
+    // Shifts the bits one at a time, treating the bytes as one little endian number
+    fn shift_bit_by_bit(bytes: &[u8], by: u32, left: bool) -> Vec<u8> {
+        let bit = |i: isize| {
+            i >= 0 && (i as usize) < bytes.len() * 8 && bytes[i as usize / 8] >> (i % 8) & 1 == 1
+        };
+        let by = if left { by as isize } else { -(by as isize) };
+        (0..bytes.len())
+            .map(|byte| {
+                (0..8).fold(0u8, |acc, i| {
+                    acc | (bit((byte * 8 + i) as isize - by) as u8) << i
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shift_kernels_match_bit_by_bit() {
+        for &len in LENGTHS.iter() {
+            let src = random_bytes(len, 13);
+            for by in 1..8 {
+                let left = shift_bit_by_bit(&src, by, true);
+                let right = shift_bit_by_bit(&src, by, false);
+
+                let mut actual = src.clone();
+                scalar::shl_bits(&mut actual, by);
+                assert_eq!(actual, left, "length {} by {}", len, by);
+                let mut actual = src.clone();
+                scalar::shr_bits(&mut actual, by);
+                assert_eq!(actual, right, "length {} by {}", len, by);
+
+                let mut actual = src.clone();
+                simd::shl_bits(&mut actual, by);
+                assert_eq!(actual, left, "length {} by {}", len, by);
+                let mut actual = src.clone();
+                simd::shr_bits(&mut actual, by);
+                assert_eq!(actual, right, "length {} by {}", len, by);
+
+                #[cfg(target_arch = "x86_64")]
+                {
+                    let mut actual = src.clone();
+                    unsafe { simd::sse2::shl_bits(&mut actual, by) };
+                    assert_eq!(actual, left, "length {} by {}", len, by);
+                    let mut actual = src.clone();
+                    unsafe { simd::sse2::shr_bits(&mut actual, by) };
+                    assert_eq!(actual, right, "length {} by {}", len, by);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn count_ones_matches_scalar() {
+        for &len in LENGTHS.iter() {
+            let src = random_bytes(len, 11);
+            let expected: usize = src.iter().map(|b| b.count_ones() as usize).sum();
+            assert_eq!(scalar::count_ones(&src), expected);
+            assert_eq!(simd::count_ones(&src), expected, "length {}", len);
+        }
+    }
+}